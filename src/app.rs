@@ -1,25 +1,38 @@
-use netstat2::{
-    AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, SocketInfo, get_sockets_info,
-};
+use chrono::{DateTime, Local};
+use netstat2::{AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, get_sockets_info};
 use num_enum::TryFromPrimitive;
+use serde::{Deserialize, Serialize};
 use std::{
     cell::{Cell, RefCell},
     cmp::Ordering,
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    io::Write,
     net::IpAddr,
+    ops::RangeInclusive,
     process::Command,
     time::{Duration, Instant},
 };
-use sysinfo::System;
+use sysinfo::{Pid, ProcessRefreshKind, Signal, System};
+use unicode_width::UnicodeWidthStr;
 
+use crate::cli::Cli;
+use crate::clipboard;
+use crate::config::Config;
 use crate::event::{AppEvent, Event, EventHandler};
+use crate::export::{self, ExportFormat};
+use crate::keys::{KeyBindings, KeyContext};
+use crate::recording::{ProcessRecord, Snapshot, SocketRecord, Source};
+use crate::theme::{self, Theme};
+#[cfg(target_os = "linux")]
+use crate::{cgroup, procfs};
 use ratatui::{
     DefaultTerminal,
-    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    layout::{Position, Rect},
     widgets::Row,
 };
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, TryFromPrimitive)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, TryFromPrimitive, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum SortColumn {
     Proto = 1,
@@ -31,6 +44,113 @@ pub enum SortColumn {
     PID = 7,
     Process = 8,
     DataRate = 9,
+    User = 10,
+    Namespace = 11,
+    Cgroup = 12,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 12] = [
+        SortColumn::Proto,
+        SortColumn::LocalIP,
+        SortColumn::LocalPort,
+        SortColumn::RemoteIP,
+        SortColumn::RemotePort,
+        SortColumn::State,
+        SortColumn::PID,
+        SortColumn::Process,
+        SortColumn::DataRate,
+        SortColumn::User,
+        SortColumn::Namespace,
+        SortColumn::Cgroup,
+    ];
+
+    /// Header label of the column
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortColumn::Proto => "Prot",
+            SortColumn::LocalIP => "Local IP",
+            SortColumn::LocalPort => "LPort",
+            SortColumn::RemoteIP => "Remote IP",
+            SortColumn::RemotePort => "RPort",
+            SortColumn::State => "State",
+            SortColumn::PID => "PID",
+            SortColumn::Process => "Process",
+            SortColumn::DataRate => "Rate",
+            SortColumn::User => "User",
+            SortColumn::Namespace => "Namespace",
+            SortColumn::Cgroup => "Unit/Container",
+        }
+    }
+
+    /// Columns with lower priority are dropped first if the terminal is too narrow
+    pub fn priority(&self) -> u8 {
+        match self {
+            SortColumn::LocalIP | SortColumn::LocalPort => 12,
+            SortColumn::RemoteIP | SortColumn::RemotePort => 11,
+            SortColumn::Process => 10,
+            SortColumn::DataRate => 9,
+            SortColumn::Proto => 8,
+            SortColumn::State => 7,
+            SortColumn::PID => 6,
+            SortColumn::User => 5,
+            SortColumn::Cgroup => 4,
+            SortColumn::Namespace => 3,
+        }
+    }
+
+    /// Maximum width of the column in the connection table if no width is configured
+    pub fn width(&self) -> u16 {
+        match self {
+            SortColumn::Proto => 7,
+            SortColumn::LocalIP => 40,
+            SortColumn::LocalPort => 5,
+            SortColumn::RemoteIP => 40,
+            SortColumn::RemotePort => 5,
+            SortColumn::State => 11,
+            SortColumn::PID => 11,
+            SortColumn::Process => 25,
+            SortColumn::DataRate => 10,
+            SortColumn::User => 12,
+            SortColumn::Namespace => 20,
+            SortColumn::Cgroup => 24,
+        }
+    }
+}
+
+/// Position, visibility and width of a column of the connection table
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ColumnLayout {
+    pub column: SortColumn,
+    pub visible: bool,
+    /// Fixed width; `None` fits the width to the content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+}
+
+impl ColumnLayout {
+    /// All columns in their default order, shown with content based widths
+    pub fn defaults() -> Vec<ColumnLayout> {
+        SortColumn::ALL
+            .iter()
+            .map(|column| ColumnLayout {
+                column: *column,
+                visible: true,
+                width: None,
+            })
+            .collect()
+    }
+
+    /// Removes duplicate columns of a loaded layout and appends missing ones.
+    pub fn normalize(layout: &[ColumnLayout]) -> Vec<ColumnLayout> {
+        let mut columns: Vec<ColumnLayout> = Vec::new();
+        for column in layout.iter().chain(Self::defaults().iter()) {
+            if !columns.iter().any(|c| c.column == column.column) {
+                columns.push(*column);
+            }
+        }
+        columns
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -39,6 +159,15 @@ pub enum SortOrder {
     Desc,
 }
 
+impl SortOrder {
+    pub fn reversed(&self) -> SortOrder {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum IpVersionFilter {
     Ipv4Only,
@@ -46,11 +175,102 @@ pub enum IpVersionFilter {
     Ipv4AndIpv6,
 }
 
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Raw,
+    Icmp,
+    Sctp,
+}
+
+impl Protocol {
+    pub const ALL: [Protocol; 5] = [
+        Protocol::Tcp,
+        Protocol::Udp,
+        Protocol::Raw,
+        Protocol::Icmp,
+        Protocol::Sctp,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+            Protocol::Raw => "RAW",
+            Protocol::Icmp => "ICMP",
+            Protocol::Sctp => "SCTP",
+        }
+    }
+
+    /// Name shown in the protocol column, e.g. "TCPv4"
+    pub fn display_name(&self, addr: &IpAddr) -> String {
+        if addr.is_ipv4() {
+            format!("{}v4", self.label())
+        } else {
+            format!("{}v6", self.label())
+        }
+    }
+}
+
+/// Set of protocols whose sockets are shown
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum ProtocolFilter {
-    TcpOnly,
-    UdpOnly,
-    TcpAndUdp,
+pub struct ProtocolFilter {
+    shown: [bool; Protocol::ALL.len()],
+}
+
+impl Default for ProtocolFilter {
+    fn default() -> Self {
+        Self {
+            shown: [true; Protocol::ALL.len()],
+        }
+    }
+}
+
+impl ProtocolFilter {
+    pub fn is_shown(&self, protocol: Protocol) -> bool {
+        self.shown[protocol as usize]
+    }
+
+    pub fn toggle(&mut self, protocol: Protocol) {
+        self.shown[protocol as usize] = !self.shown[protocol as usize];
+    }
+
+    /// Short description like "TCP+UDP" or "all"
+    pub fn summary(&self) -> String {
+        if self.shown.iter().all(|shown| *shown) {
+            return "all".to_string();
+        }
+        let shown = Protocol::ALL
+            .iter()
+            .filter(|protocol| self.is_shown(**protocol))
+            .map(Protocol::label)
+            .collect::<Vec<_>>();
+        if shown.is_empty() {
+            "none".to_string()
+        } else {
+            shown.join("+")
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum UserFilter {
+    AllUsers,
+    /// Only sockets owned by the user running netview
+    CurrentUser,
+    User(String),
+}
+
+/// How long a message stays in the status bar
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
+/// A transient message of the status bar, e.g. the result of an action or an error
+#[derive(Clone, Debug)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    pub since: Instant,
 }
 
 #[derive(Clone, Debug)]
@@ -60,14 +280,32 @@ pub struct ConnectionEntry {
     pub local_port: u16,
     pub remote_ip: String,
     pub remote_port: u16,
+    /// Numeric addresses for sorting; `local_ip` and `remote_ip` may be resolved names
+    pub local_addr: IpAddr,
+    /// None if the socket has no remote end, e.g. a listening UDP socket
+    pub remote_addr: Option<IpAddr>,
     pub state: String,
+    /// The owning process; the lowest PID if the socket is shared
     pub pid: u32,
+    /// All processes holding the socket, sorted ascending (e.g. forked server workers)
+    pub pids: Vec<u32>,
     pub process: String,
+    /// Name of the user owning the socket, or its numeric UID if the name is unknown
+    pub user: String,
+    /// Network namespace or container of the socket; empty unless all namespaces are shown
+    pub namespace: String,
+    /// Container, Kubernetes pod or systemd unit of the owning process (Linux only)
+    pub cgroup: String,
+    /// Socket inode, used to match file descriptors of a process (Linux only, 0 if unknown)
+    pub inode: u64,
+    /// When netview first saw the connection, on the clock of the refreshes
     pub creation_time: Instant,
     pub rx_bytes: u64,
     pub tx_bytes: u64,
     pub data_rate: String, // Display string like "1.2 MB/s"
     pub last_update: Instant,
+    /// The connection is gone but its row is kept while the row order is frozen
+    pub closed: bool,
 }
 
 impl PartialEq for ConnectionEntry {
@@ -79,6 +317,7 @@ impl PartialEq for ConnectionEntry {
             && self.remote_port == other.remote_port
             && self.state == other.state
             && self.pid == other.pid
+            && self.namespace == other.namespace
     }
 }
 
@@ -94,6 +333,7 @@ impl Ord for ConnectionEntry {
             .then(self.remote_port.cmp(&other.remote_port))
             .then(self.state.cmp(&other.state))
             .then(self.pid.cmp(&other.pid))
+            .then(self.namespace.cmp(&other.namespace))
     }
 }
 
@@ -104,6 +344,72 @@ impl PartialOrd for ConnectionEntry {
 }
 
 impl ConnectionEntry {
+    /// PID column text, e.g. "1234 (+3)" if three more processes share the socket
+    pub fn pid_label(&self) -> String {
+        if self.pids.len() > 1 {
+            format!("{} (+{})", self.pid, self.pids.len() - 1)
+        } else {
+            self.pid.to_string()
+        }
+    }
+
+    /// Text shown in `column` of the connection table
+    pub fn column_text(&self, column: SortColumn) -> String {
+        match column {
+            SortColumn::Proto => self.proto.clone(),
+            SortColumn::LocalIP => self.local_ip.clone(),
+            SortColumn::LocalPort => self.local_port.to_string(),
+            SortColumn::RemoteIP => self.remote_ip.clone(),
+            SortColumn::RemotePort if self.remote_port == 0 => "".to_string(),
+            SortColumn::RemotePort => self.remote_port.to_string(),
+            SortColumn::State => self.state.clone(),
+            SortColumn::PID => self.pid_label(),
+            SortColumn::Process => self.process.clone(),
+            SortColumn::DataRate => self.data_rate.clone(),
+            SortColumn::User => self.user.clone(),
+            SortColumn::Namespace => self.namespace.clone(),
+            SortColumn::Cgroup => self.cgroup.clone(),
+        }
+    }
+
+    /// `ip:port` of the local or remote end with brackets around IPv6 addresses, or only the
+    /// address if there is no port
+    pub fn endpoint(&self, ip: SortColumn, port: SortColumn) -> String {
+        let ip = self.column_text(ip);
+        let port = self.column_text(port);
+        if ip.is_empty() || port.is_empty() {
+            ip
+        } else if ip.contains(':') {
+            format!("[{ip}]:{port}")
+        } else {
+            format!("{ip}:{port}")
+        }
+    }
+
+    /// Identity of the connection across refreshes, independent of DNS resolution
+    pub fn id(&self) -> ConnectionId {
+        ConnectionId {
+            proto: self.proto.clone(),
+            local_addr: self.local_addr,
+            local_port: self.local_port,
+            remote_addr: self.remote_addr,
+            remote_port: self.remote_port,
+            pid: self.pid,
+            namespace: self.namespace.clone(),
+        }
+    }
+
+    /// Key of the byte counters of the connection kept for the rate of the next refresh
+    fn rate_key(&self) -> String {
+        rate_key(
+            &self.namespace,
+            &self.local_ip,
+            self.local_port,
+            &self.remote_ip,
+            self.remote_port,
+        )
+    }
+
     pub fn get_id(&self) -> String {
         format!(
             "{}:{}:{}:{}",
@@ -112,6 +418,221 @@ impl ConnectionEntry {
     }
 }
 
+/// Identifies a connection across refreshes, e.g. to keep marks and row positions
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct ConnectionId {
+    proto: String,
+    local_addr: IpAddr,
+    local_port: u16,
+    remote_addr: Option<IpAddr>,
+    remote_port: u16,
+    pid: u32,
+    namespace: String,
+}
+
+/// Entries of the bulk action menu, applied to the marked connections
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BulkAction {
+    /// Copy the rows to the clipboard
+    Copy,
+    /// Write the rows to a file in the current directory
+    Write,
+    Terminate,
+    Hangup,
+    Interrupt,
+    Kill,
+}
+
+impl BulkAction {
+    pub const ALL: [BulkAction; 6] = [
+        BulkAction::Copy,
+        BulkAction::Write,
+        BulkAction::Terminate,
+        BulkAction::Hangup,
+        BulkAction::Interrupt,
+        BulkAction::Kill,
+    ];
+
+    /// The signal sent to the owning processes, with its name
+    pub fn signal(&self) -> Option<(Signal, &'static str)> {
+        match self {
+            BulkAction::Copy | BulkAction::Write => None,
+            BulkAction::Terminate => Some((Signal::Term, "SIGTERM")),
+            BulkAction::Hangup => Some((Signal::Hangup, "SIGHUP")),
+            BulkAction::Interrupt => Some((Signal::Interrupt, "SIGINT")),
+            BulkAction::Kill => Some((Signal::Kill, "SIGKILL")),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match (self, self.signal()) {
+            (BulkAction::Copy, _) => "Copy to clipboard".to_string(),
+            (BulkAction::Write, _) => "Write to file".to_string(),
+            (_, Some((_, name))) => format!("Send {name} to owning processes"),
+            (_, None) => String::new(),
+        }
+    }
+}
+
+/// A signal of a bulk action waiting for confirmation
+#[derive(Clone, Debug)]
+pub struct PendingSignal {
+    pub signal: Signal,
+    /// Name of the signal, e.g. "SIGTERM"
+    pub name: &'static str,
+    /// (PID, process name, start time) of the receivers, sorted by PID. The start time is
+    /// `None` if the process already exited.
+    pub processes: Vec<(u32, String, Option<u64>)>,
+    /// Owning processes left out because they must not be signalled: PID 1 and netview itself
+    pub skipped: Vec<u32>,
+}
+
+/// Entries of the copy menu; each copies the selected connection through a template
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CopyField {
+    /// The configured copy template
+    Row,
+    Remote,
+    Local,
+    RemoteIp,
+    Pid,
+    CommandLine,
+}
+
+impl CopyField {
+    pub const ALL: [CopyField; 6] = [
+        CopyField::Row,
+        CopyField::Remote,
+        CopyField::Local,
+        CopyField::RemoteIp,
+        CopyField::Pid,
+        CopyField::CommandLine,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CopyField::Row => "Row",
+            CopyField::Remote => "Remote address",
+            CopyField::Local => "Local address",
+            CopyField::RemoteIp => "Remote IP",
+            CopyField::Pid => "PID",
+            CopyField::CommandLine => "Command line",
+        }
+    }
+
+    /// Template of the copied text; `None` for the configured template
+    pub fn template(&self) -> Option<&'static str> {
+        match self {
+            CopyField::Row => None,
+            CopyField::Remote => Some("{remote}"),
+            CopyField::Local => Some("{local}"),
+            CopyField::RemoteIp => Some("{remote_ip}"),
+            CopyField::Pid => Some("{pid}"),
+            CopyField::CommandLine => Some("{cmdline}"),
+        }
+    }
+}
+
+/// Tabs of the process info view
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ProcessTab {
+    /// Properties of the process
+    Info,
+    /// Ancestors and children of the process
+    Tree,
+    /// Connections owned by the process
+    Sockets,
+    /// Open file descriptors of the process
+    Files,
+    /// Environment variables of the process
+    Environment,
+}
+
+impl ProcessTab {
+    pub const ALL: [ProcessTab; 5] = [
+        ProcessTab::Info,
+        ProcessTab::Tree,
+        ProcessTab::Sockets,
+        ProcessTab::Files,
+        ProcessTab::Environment,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ProcessTab::Info => "Info",
+            ProcessTab::Tree => "Tree",
+            ProcessTab::Sockets => "Sockets",
+            ProcessTab::Files => "Files",
+            ProcessTab::Environment => "Environment",
+        }
+    }
+}
+
+/// A line of the process tree
+#[derive(Clone, Debug)]
+pub struct ProcessTreeNode {
+    pub pid: u32,
+    pub name: String,
+    /// Indentation level; the root ancestor has depth 0
+    pub depth: usize,
+    /// Number of sockets owned by the process, including those hidden by filters
+    pub socket_count: usize,
+}
+
+/// Order of the environment variables tab
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EnvironmentSort {
+    Name,
+    Value,
+}
+
+impl EnvironmentSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnvironmentSort::Name => "name",
+            EnvironmentSort::Value => "value",
+        }
+    }
+}
+
+/// An open file descriptor of a process
+#[derive(Clone, Debug)]
+pub struct ProcessFile {
+    pub fd: u32,
+    /// File, Pipe, Socket, Anon inode or Other
+    pub kind: &'static str,
+    /// Link target, e.g. a path, `pipe:[1234]` or `socket:[5678]`
+    pub target: String,
+    /// Inode of the socket if the descriptor is a socket
+    pub socket_inode: Option<u64>,
+}
+
+impl ProcessFile {
+    fn new(fd: u32, target: String) -> Self {
+        #[cfg(target_os = "linux")]
+        let socket_inode = procfs::parse_socket_link(&target);
+        #[cfg(not(target_os = "linux"))]
+        let socket_inode = None;
+        let kind = if socket_inode.is_some() {
+            "Socket"
+        } else if target.starts_with("pipe:") {
+            "Pipe"
+        } else if target.starts_with("anon_inode:") {
+            "Anon inode"
+        } else if target.starts_with('/') {
+            "File"
+        } else {
+            "Other"
+        };
+        Self {
+            fd,
+            kind,
+            target,
+            socket_inode,
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Debug)]
 pub enum UiState {
     /// Showing the main frame containing the connections table
@@ -120,8 +641,33 @@ pub enum UiState {
     Help,
     /// Showing info about the process of the selected connection
     ProcessInfo,
+    /// Showing the protocol selection overlay
+    ProtocolFilter,
+    /// Showing the user selection overlay
+    UserFilter,
+    /// Showing the network namespace selection overlay
+    NamespaceFilter,
+    /// Showing the unit/container selection overlay
+    CgroupFilter,
+    /// Showing the column chooser overlay
+    ColumnChooser,
+    /// Showing the bulk action menu for the marked connections
+    BulkActions,
+    /// Showing the menu of fields to copy
+    CopyMenu,
+    /// Showing the export format selection
+    ExportMenu,
+    /// Asking whether to send a signal to the listed processes
+    ConfirmSignal,
 }
 
+/// Time between ticks while replaying, the resolution of the replay timing
+const REPLAY_TICK_RATE: Duration = Duration::from_millis(100);
+
+/// Shortest time between two refreshes of the live connections, and between two recorded
+/// snapshots
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Application.
 #[derive(Debug)]
 pub struct App {
@@ -133,6 +679,14 @@ pub struct App {
     pub entries: Vec<ConnectionEntry>,
     /// Vertical scroll position of connection table
     pub scroll_connection_table: Cell<usize>,
+    /// Header cells of the last drawn connection table with the column a click sorts by
+    pub header_click_areas: RefCell<Vec<(Rect, SortColumn)>>,
+    /// Area of the rows of the last drawn connection table
+    pub connection_rows_area: Cell<Rect>,
+    /// Lines per row of the last drawn connection table
+    pub connection_row_height: Cell<usize>,
+    /// Time and connection index of the last click, to detect double clicks
+    last_click: Option<(Instant, usize)>,
     /// Length of process info list
     pub process_info_list_length: Cell<usize>,
     /// Vertical scroll position of process info
@@ -143,14 +697,66 @@ pub struct App {
     pub sort_column: SortColumn,
     /// Sort ascending or descending
     pub sort_order: SortOrder,
+    /// Further sort keys for rows equal in the sort column, by priority
+    pub then_sort_by: Vec<(SortColumn, SortOrder)>,
+    /// Keep rows in place during refreshes; new rows are appended, closed ones marked
+    pub freeze_order: bool,
+    /// Marked connections, kept across refreshes; bulk actions apply to them
+    pub marked: HashSet<ConnectionId>,
+    /// Start of the range being marked; the range ends at the selection
+    pub visual_anchor: Option<ConnectionId>,
+    /// Signal shown for confirmation before it is sent
+    pub pending_signal: Option<PendingSignal>,
     /// The visible height of the tables
     pub visible_table_height: Cell<usize>,
     /// Filter connections by ip version
     pub ip_version_filter: IpVersionFilter,
     /// Filter connections by protocol
     pub protocol_filter: ProtocolFilter,
+    /// Filter connections by owning user
+    pub user_filter: UserFilter,
+    /// Highlighted line in a selection overlay
+    pub overlay_cursor: usize,
+    /// Name of the user running netview
+    pub current_user: String,
+    /// Owners of all sockets of the last refresh, including filtered ones
+    pub known_users: BTreeSet<String>,
+    /// Collect sockets of all network namespaces, not only netview's own (Linux only)
+    pub all_namespaces: bool,
+    /// Show only sockets of this network namespace
+    pub namespace_filter: Option<String>,
+    /// Network namespaces of the last refresh, including filtered ones
+    pub known_namespaces: BTreeSet<String>,
+    /// Show only sockets of processes in this unit, pod or container
+    pub cgroup_filter: Option<String>,
+    /// Units, pods and containers of the last refresh
+    pub known_cgroups: BTreeSet<String>,
+    /// Sockets of the last refresh per protocol label, before any filter
+    pub connection_totals: BTreeMap<&'static str, usize>,
+    /// Sockets of the last refresh per owning PID, before any filter
+    pub pid_socket_counts: HashMap<u32, usize>,
+    /// Received and sent bytes per second of the shown connections
+    pub throughput: (u64, u64),
+    /// Time and duration of the last connection list refresh
+    pub last_refresh: Option<(DateTime<Local>, Duration)>,
+    /// Message shown in the status bar until it expires
+    pub status_message: Option<StatusMessage>,
+    /// Settings saved in the config file, e.g. the column layout
+    pub config: Config,
+    /// Why the config file could not be loaded; it is not overwritten then
+    pub config_error: Option<String>,
+    /// Colors of the user interface
+    pub theme: Theme,
+    /// Active key bindings: the defaults changed by the config
+    pub key_bindings: KeyBindings,
+    /// First shown line of the help overlay
+    pub scroll_help: Cell<usize>,
+    /// User names by UID
+    pub(crate) users: sysinfo::Users,
     /// Resolve names of ip addresses
     pub resolve_address_names: bool,
+    /// Sort address columns by resolved name instead of numerically (with DNS resolution)
+    pub sort_addresses_by_name: bool,
     /// Show process info
     pub show_process_info: bool,
     /// Cache for DNS name resolutions
@@ -161,6 +767,40 @@ pub struct App {
     pub selected_index: Option<usize>,
     /// Ui state
     pub ui_state: UiState,
+    /// Live sockets, possibly recorded, or a replayed recording
+    pub source: Source,
+    /// Process shown in the process info view
+    pub process_info_pid: Option<u32>,
+    /// Active tab of the process info view
+    pub process_tab: ProcessTab,
+    /// Ancestors, the process itself and its children, root first
+    pub process_tree: Vec<ProcessTreeNode>,
+    /// Highlighted line of the process tree
+    pub process_tree_cursor: usize,
+    /// Highlighted line of the sockets tab
+    pub process_socket_cursor: usize,
+    /// Open file descriptors of the shown process (Linux only)
+    pub process_files: Vec<ProcessFile>,
+    /// Highlighted line of the files tab
+    pub process_file_cursor: usize,
+    /// Environment variables of the shown process as (name, value)
+    pub process_environment: Vec<(String, String)>,
+    /// Order of the environment variables tab
+    pub environment_sort: EnvironmentSort,
+    /// Show only environment variables whose name or value contains this text
+    pub environment_search: String,
+    /// true, while typing the environment search
+    pub environment_search_active: bool,
+    /// Search in the connection table; matches are highlighted, not filtered
+    pub table_search: String,
+    /// True while the table search is typed
+    pub table_search_active: bool,
+    /// Hide values of variables that look like secrets (tokens, passwords, keys)
+    pub mask_secrets: bool,
+    /// Processes for the process info view, kept between refreshes to measure CPU usage
+    pub process_system: System,
+    /// Recent (CPU usage in percent, memory in bytes) samples of the shown process, oldest first
+    pub process_history: VecDeque<(f32, u64)>,
     /// Last time the connection list was refreshed (for throttling).
     last_connection_refresh: RefCell<Option<Instant>>,
     /// Last time the user pressed a key (skip heavy refresh while actively scrolling).
@@ -169,33 +809,93 @@ pub struct App {
     pub(crate) process_info_cache: RefCell<Option<(u32, usize, Vec<Row<'static>>)>>,
     /// Previous connection data for rate calculation: (rx_bytes, tx_bytes, timestamp)
     previous_connections: RefCell<HashMap<String, (u64, u64, Instant)>>,
+    /// When each socket of the last refresh was first seen, including filtered ones
+    first_seen: HashMap<ConnectionId, Instant>,
 }
 
 impl Default for App {
     fn default() -> Self {
+        Self::with_events(EventHandler::new())
+    }
+}
+
+impl App {
+    /// An application with default settings, receiving `events`
+    fn with_events(events: EventHandler) -> Self {
         Self {
             running: true,
-            events: EventHandler::new(),
+            events,
             entries: vec![],
             scroll_connection_table: Cell::new(0),
+            header_click_areas: RefCell::new(Vec::new()),
+            connection_rows_area: Cell::new(Rect::default()),
+            connection_row_height: Cell::new(1),
+            last_click: None,
             process_info_list_length: Cell::new(0),
             scroll_process_info: Cell::new(0),
             paused: false,
             sort_column: SortColumn::LocalPort,
             sort_order: SortOrder::Asc,
+            then_sort_by: Vec::new(),
+            freeze_order: false,
+            marked: HashSet::new(),
+            visual_anchor: None,
+            pending_signal: None,
             visible_table_height: Cell::new(0),
             ip_version_filter: IpVersionFilter::Ipv4AndIpv6,
-            protocol_filter: ProtocolFilter::TcpAndUdp,
+            protocol_filter: ProtocolFilter::default(),
+            user_filter: UserFilter::AllUsers,
+            overlay_cursor: 0,
+            current_user: current_user_name(),
+            known_users: BTreeSet::new(),
+            all_namespaces: false,
+            namespace_filter: None,
+            known_namespaces: BTreeSet::new(),
+            cgroup_filter: None,
+            known_cgroups: BTreeSet::new(),
+            connection_totals: BTreeMap::new(),
+            pid_socket_counts: HashMap::new(),
+            throughput: (0, 0),
+            last_refresh: None,
+            status_message: None,
+            config: Config {
+                columns: ColumnLayout::defaults(),
+                ..Config::default()
+            },
+            config_error: None,
+            theme: Theme::dark(),
+            key_bindings: KeyBindings::default(),
+            scroll_help: Cell::new(0),
+            users: sysinfo::Users::new_with_refreshed_list(),
             resolve_address_names: false,
+            sort_addresses_by_name: false,
             show_process_info: false,
             dns_cache: HashMap::new(),
             selected: None,
             selected_index: None,
             ui_state: UiState::ConnectionTable,
+            source: Source::default(),
+            process_info_pid: None,
+            process_tab: ProcessTab::Info,
+            process_tree: vec![],
+            process_tree_cursor: 0,
+            process_socket_cursor: 0,
+            process_files: vec![],
+            process_file_cursor: 0,
+            process_environment: vec![],
+            environment_sort: EnvironmentSort::Name,
+            environment_search: String::new(),
+            environment_search_active: false,
+            table_search: String::new(),
+            table_search_active: false,
+            mask_secrets: true,
+            process_system: System::new(),
+            process_history: VecDeque::new(),
             last_connection_refresh: RefCell::new(None),
             last_user_input: RefCell::new(None),
             process_info_cache: RefCell::new(None),
             previous_connections: RefCell::new(HashMap::new()),
+            first_seen: HashMap::new(),
         }
     }
 }
@@ -206,6 +906,39 @@ impl App {
         Self::default()
     }
 
+    /// Constructs a new instance of [`App`] configured by command line options, showing the
+    /// connections of `source`.
+    pub fn with_cli(cli: &Cli, source: Source) -> Self {
+        let (mut config, config_error) = match Config::load() {
+            Ok(config) => (config, None),
+            Err(error) => (Config::default(), Some(error.to_string())),
+        };
+        config.columns = ColumnLayout::normalize(&config.columns);
+        let theme = if theme::no_color() {
+            Theme::no_color()
+        } else {
+            config.active_theme()
+        };
+        // A replay advances between the slow ticks of the live view
+        let events = match source {
+            Source::Replay(_) => EventHandler::with_tick_rate(REPLAY_TICK_RATE),
+            Source::Live(_) => EventHandler::new(),
+        };
+        let mut app = Self {
+            all_namespaces: cli.all_namespaces,
+            key_bindings: KeyBindings::with_config(&config.keys),
+            config,
+            theme,
+            source,
+            ..Self::with_events(events)
+        };
+        if let Some(error) = &config_error {
+            app.show_error(format!("Config ignored: {error}"));
+        }
+        app.config_error = config_error;
+        app
+    }
+
     /// Run the application's main loop.
     #[allow(clippy::single_match)]
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
@@ -230,6 +963,10 @@ impl App {
                             self.record_user_input();
                             self.handle_key_events(key_event)?;
                         }
+                        ::ratatui::crossterm::event::Event::Mouse(mouse_event) => {
+                            self.record_user_input();
+                            self.handle_mouse_events(mouse_event);
+                        }
                         ::ratatui::crossterm::event::Event::Resize(_, _) => {
                             // Clear process info cache on resize so it recalculates with new width
                             *self.process_info_cache.borrow_mut() = None;
@@ -245,14 +982,60 @@ impl App {
                         AppEvent::Pause => self.pause(),
                         AppEvent::ScrollUpSelection => self.scroll_up_selection(),
                         AppEvent::ScrollDownSelection => self.scroll_down_selection(),
-                        AppEvent::ScrollUpPage => self.scroll_up_page(),
-                        AppEvent::ScrollDownPage => self.scroll_down_page(),
+                        AppEvent::ScrollUpPage => self.scroll_up_rows(self.page_rows()),
+                        AppEvent::ScrollDownPage => self.scroll_down_rows(self.page_rows()),
+                        AppEvent::ScrollUpHalfPage => {
+                            self.scroll_up_rows((self.page_rows() / 2).max(1))
+                        }
+                        AppEvent::ScrollDownHalfPage => {
+                            self.scroll_down_rows((self.page_rows() / 2).max(1))
+                        }
+                        AppEvent::ScrollUpRows(rows) => self.scroll_up_rows(rows),
+                        AppEvent::ScrollDownRows(rows) => self.scroll_down_rows(rows),
+                        AppEvent::SelectConnection(index) => self.select_connection(index),
+                        AppEvent::ScrollToTop => self.scroll_up_rows(usize::MAX),
+                        AppEvent::ScrollToBottom => self.scroll_down_rows(usize::MAX),
                         AppEvent::ToggleIpVersion => self.toggle_ip_version(),
                         AppEvent::ToggleProtoVersion => self.toggle_proto_version(),
+                        AppEvent::ToggleUserFilter => self.toggle_user_filter(),
+                        AppEvent::ToggleNamespaceFilter => self.toggle_namespace_filter(),
+                        AppEvent::ToggleCgroupFilter => self.toggle_cgroup_filter(),
+                        AppEvent::ToggleColumnChooser => self.toggle_column_chooser(),
+                        AppEvent::ToggleCompactRows => self.toggle_compact_rows(),
+                        AppEvent::NextTheme => self.next_theme(),
+                        AppEvent::MoveColumnUp => self.move_column(true),
+                        AppEvent::MoveColumnDown => self.move_column(false),
+                        AppEvent::ResizeColumn(delta) => self.resize_column(Some(delta)),
+                        AppEvent::ResetColumnWidth => self.resize_column(None),
+                        AppEvent::SelectItem => self.select_item(),
+                        AppEvent::ToggleAddressSort => self.toggle_address_sort(),
+                        AppEvent::ToggleFreezeOrder => self.toggle_freeze_order(),
+                        AppEvent::ToggleMark => self.toggle_mark(),
+                        AppEvent::ToggleVisualMark => self.toggle_visual_mark(),
+                        AppEvent::ClearMarks => self.clear_marks(),
+                        AppEvent::ToggleBulkActions => self.toggle_bulk_actions(),
+                        AppEvent::CopySelection => self.copy_selection(None),
+                        AppEvent::ToggleCopyMenu => self.toggle_copy_menu(),
+                        AppEvent::ToggleExportMenu => self.toggle_export_menu(),
+                        AppEvent::StepReplay(forward) => self.step_replay(forward),
+                        AppEvent::ChangeReplaySpeed(faster) => self.change_replay_speed(faster),
+                        AppEvent::ConfirmSignal => self.confirm_signal(),
                         AppEvent::ToggleDnsResolution => self.toggle_dns_resolution(),
                         AppEvent::Sort(sort_column) => self.sort_by_column(sort_column),
+                        AppEvent::ThenSort(sort_column) => self.then_sort_by_column(sort_column),
                         AppEvent::ShowHelp => self.show_help(),
                         AppEvent::ShowProcessInfo => self.show_process_info(),
+                        AppEvent::NextTab => self.next_process_tab(),
+                        AppEvent::PreviousTab => self.previous_process_tab(),
+                        AppEvent::StartSearch => self.start_search(),
+                        AppEvent::SearchInput(c) => self.search_input(Some(c)),
+                        AppEvent::SearchBackspace => self.search_input(None),
+                        AppEvent::FinishSearch => self.finish_search(),
+                        AppEvent::NextMatch => self.jump_to_match(true),
+                        AppEvent::PreviousMatch => self.jump_to_match(false),
+                        AppEvent::CancelSearch => self.cancel_search(),
+                        AppEvent::ToggleMaskSecrets => self.toggle_mask_secrets(),
+                        AppEvent::ToggleEnvironmentSort => self.toggle_environment_sort(),
                     }
                     true
                 }
@@ -263,64 +1046,149 @@ impl App {
 
     /// Handles the key events and converts them into `AppEvent`s.
     pub fn handle_key_events(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
-        match key_event.code {
-            KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
-            KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
-                self.events.send(AppEvent::Quit)
-            }
-            KeyCode::Pause | KeyCode::Char(' ') => self.events.send(AppEvent::Pause),
-            KeyCode::Enter => self.events.send(AppEvent::ShowProcessInfo),
-            KeyCode::Up => self.events.send(AppEvent::ScrollUpSelection),
-            KeyCode::Down => self.events.send(AppEvent::ScrollDownSelection),
-            KeyCode::PageUp => self.events.send(AppEvent::ScrollUpPage),
-            KeyCode::PageDown => self.events.send(AppEvent::ScrollDownPage),
-            KeyCode::Char('i' | 'I') => self.events.send(AppEvent::ToggleIpVersion),
-            KeyCode::Char('p' | 'P') => self.events.send(AppEvent::ToggleProtoVersion),
-            KeyCode::Char('d' | 'D') => self.events.send(AppEvent::ToggleDnsResolution),
-            KeyCode::Char('h' | 'H') => self.events.send(AppEvent::ShowHelp),
-            KeyCode::Char('1') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(1)?)),
-            KeyCode::Char('2') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(2)?)),
-            KeyCode::Char('3') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(3)?)),
-            KeyCode::Char('4') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(4)?)),
-            KeyCode::Char('5') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(5)?)),
-            KeyCode::Char('6') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(6)?)),
-            KeyCode::Char('7') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(7)?)),
-            KeyCode::Char('8') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(8)?)),
-            KeyCode::Char('9') => self
-                .events
-                .send(AppEvent::Sort(SortColumn::try_from_primitive(9)?)),
-            // Other handlers you could add here.
-            _ => {}
+        // Typing a search is not remappable, every character goes into the search
+        if self.environment_search_active || self.table_search_active {
+            match key_event.code {
+                KeyCode::Esc => self.events.send(AppEvent::CancelSearch),
+                KeyCode::Enter => self.events.send(AppEvent::FinishSearch),
+                KeyCode::Backspace => self.events.send(AppEvent::SearchBackspace),
+                KeyCode::Char(c) => self.events.send(AppEvent::SearchInput(c)),
+                _ => {}
+            }
+            return Ok(());
+        }
+        // Signals are only sent on an explicit yes; Enter does not confirm
+        if self.ui_state == UiState::ConfirmSignal {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.events.send(AppEvent::ConfirmSignal);
+                    return Ok(());
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.events.send(AppEvent::Quit);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        let action = self
+            .key_contexts()
+            .iter()
+            .find_map(|context| self.key_bindings.lookup(*context, &key_event));
+        if let Some(action) = action {
+            self.events.send(action.event());
         }
         Ok(())
     }
 
+    /// Handles mouse clicks and wheel scrolling and converts them into `AppEvent`s.
+    pub fn handle_mouse_events(&mut self, mouse_event: MouseEvent) {
+        /// Lines scrolled by one step of the mouse wheel
+        const WHEEL_ROWS: usize = 3;
+        /// Longest time between the clicks of a double click
+        const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => self.events.send(AppEvent::ScrollUpRows(WHEEL_ROWS)),
+            MouseEventKind::ScrollDown => self.events.send(AppEvent::ScrollDownRows(WHEEL_ROWS)),
+            MouseEventKind::Down(MouseButton::Left)
+                if self.ui_state == UiState::ConnectionTable =>
+            {
+                let position = Position::new(mouse_event.column, mouse_event.row);
+                let sort_column = self
+                    .header_click_areas
+                    .borrow()
+                    .iter()
+                    .find(|(area, _)| area.contains(position))
+                    .map(|(_, column)| *column);
+                if let Some(sort_column) = sort_column {
+                    // Shift-click adds a secondary sort key, like Shift and a digit
+                    if mouse_event.modifiers.contains(KeyModifiers::SHIFT) {
+                        self.events.send(AppEvent::ThenSort(sort_column));
+                    } else {
+                        self.events.send(AppEvent::Sort(sort_column));
+                    }
+                    return;
+                }
+                let rows_area = self.connection_rows_area.get();
+                if !rows_area.contains(position) {
+                    return;
+                }
+                let index = self.scroll_connection_table.get()
+                    + (position.y - rows_area.y) as usize / self.connection_row_height.get();
+                if index >= self.entries.len() {
+                    return;
+                }
+                let now = Instant::now();
+                match self.last_click {
+                    Some((time, clicked)) if clicked == index && now - time <= DOUBLE_CLICK => {
+                        self.last_click = None;
+                        self.events.send(AppEvent::ShowProcessInfo);
+                    }
+                    _ => {
+                        self.last_click = Some((now, index));
+                        self.events.send(AppEvent::SelectConnection(index));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Key binding contexts of the current view, most specific first
+    pub fn key_contexts(&self) -> Vec<KeyContext> {
+        match self.ui_state {
+            UiState::ColumnChooser => {
+                vec![KeyContext::Columns, KeyContext::Overlay, KeyContext::Global]
+            }
+            UiState::ProcessInfo if self.process_tab == ProcessTab::Environment => {
+                vec![KeyContext::Environment, KeyContext::Global]
+            }
+            _ if self.is_selection_overlay() => vec![KeyContext::Overlay, KeyContext::Global],
+            _ => vec![KeyContext::Global],
+        }
+    }
+
     /// Handles the tick event of the terminal.
     ///
     /// The tick event is where you can update the state of your application with any logic that
     /// needs to be updated at a fixed frame rate. E.g. polling a server, updating an animation.
     /// Returns true if the connection list was refreshed (caller should redraw).
     fn tick(&mut self) -> bool {
-        if self.paused || self.ui_state != UiState::ConnectionTable {
+        let message_expired = self
+            .status_message
+            .as_ref()
+            .is_some_and(|message| message.since.elapsed() >= STATUS_MESSAGE_DURATION);
+        if message_expired {
+            self.status_message = None;
+        }
+        let refreshed = self.refresh_on_tick();
+        self.record_on_tick();
+        refreshed || message_expired
+    }
+
+    /// Refreshes the shown connections or process, unless paused or the user is busy.
+    /// Returns true if something was refreshed.
+    fn refresh_on_tick(&mut self) -> bool {
+        if self.paused
+            || !matches!(
+                self.ui_state,
+                UiState::ConnectionTable | UiState::ProcessInfo
+            )
+        {
             return false;
         }
-        const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+        if let Source::Replay(replay) = &self.source {
+            let (due, last) = (replay.due(Instant::now()), replay.len() - 1);
+            if due == replay.position() {
+                return false;
+            }
+            self.show_snapshot(due);
+            if due == last {
+                self.paused = true;
+                self.show_message("End of the recording");
+            }
+            return true;
+        }
         /// Skip heavy refresh shortly after user input so scrolling stays smooth.
         const IDLE_BEFORE_REFRESH: Duration = Duration::from_millis(400);
         let now = Instant::now();
@@ -336,7 +1204,17 @@ impl App {
             .borrow()
             .is_none_or(|t| now.saturating_duration_since(t) >= REFRESH_INTERVAL);
         if should_refresh {
-            self.update_connection_entries();
+            if self.ui_state == UiState::ProcessInfo {
+                if matches!(self.process_tab, ProcessTab::Sockets | ProcessTab::Files) {
+                    self.update_connection_entries();
+                    self.process_socket_cursor = self
+                        .process_socket_cursor
+                        .min(self.process_sockets().len().saturating_sub(1));
+                }
+                self.refresh_process_stats();
+            } else {
+                self.update_connection_entries();
+            }
             *self.last_connection_refresh.borrow_mut() = Some(Instant::now());
             true
         } else {
@@ -344,6 +1222,32 @@ impl App {
         }
     }
 
+    /// Records a snapshot if none was recorded with a refresh of the shown connections lately,
+    /// so the recording goes on while the view is paused, an overlay is open or the user types.
+    fn record_on_tick(&mut self) {
+        let Source::Live(Some(recorder)) = &self.source else {
+            return;
+        };
+        if recorder
+            .last_write
+            .is_some_and(|time| time.elapsed() < REFRESH_INTERVAL)
+        {
+            return;
+        }
+        let snapshot = self.collect_sockets();
+        self.record(&snapshot);
+    }
+
+    /// Appends `snapshot` to the recording, if there is one; stops recording on errors
+    fn record(&mut self, snapshot: &Snapshot) {
+        if let Source::Live(Some(recorder)) = &mut self.source
+            && let Err(error) = recorder.write(snapshot)
+        {
+            self.source = Source::Live(None);
+            self.show_error(format!("Recording stopped: {error}"));
+        }
+    }
+
     /// Records that the user pressed a key; used to defer heavy refresh while scrolling.
     fn record_user_input(&self) {
         *self.last_user_input.borrow_mut() = Some(Instant::now());
@@ -355,6 +1259,10 @@ impl App {
         if self.ui_state != UiState::ConnectionTable {
             return;
         }
+        // Other filters or names change the rows, a frozen order starts over
+        if self.freeze_order {
+            self.entries.clear();
+        }
         self.update_connection_entries();
         *self.last_connection_refresh.borrow_mut() = Some(Instant::now());
     }
@@ -362,6 +1270,8 @@ impl App {
     /// Set running to false to quit the application.
     fn quit(&mut self) {
         match self.ui_state {
+            // Leaving a visual range comes before leaving the application
+            UiState::ConnectionTable if self.visual_anchor.is_some() => self.visual_anchor = None,
             UiState::ConnectionTable => self.running = false,
             UiState::Help => {
                 self.ui_state = UiState::ConnectionTable;
@@ -369,7 +1279,32 @@ impl App {
             UiState::ProcessInfo => {
                 self.scroll_process_info.set(0);
                 self.process_info_cache.replace(None);
+                self.process_info_pid = None;
+                self.process_tab = ProcessTab::Info;
+                self.environment_search.clear();
+                self.ui_state = UiState::ConnectionTable;
+            }
+            UiState::ProtocolFilter
+            | UiState::UserFilter
+            | UiState::NamespaceFilter
+            | UiState::CgroupFilter => {
+                self.ui_state = UiState::ConnectionTable;
+                self.refresh_connection_list();
+            }
+            UiState::ColumnChooser => {
+                self.ui_state = UiState::ConnectionTable;
+                // The layout still applies to this session if it cannot be saved
+                if self.save_config() {
+                    self.show_message("Column layout saved");
+                }
+            }
+            UiState::BulkActions | UiState::CopyMenu | UiState::ExportMenu => {
+                self.ui_state = UiState::ConnectionTable
+            }
+            UiState::ConfirmSignal => {
+                self.pending_signal = None;
                 self.ui_state = UiState::ConnectionTable;
+                self.show_message("No signal sent");
             }
         }
     }
@@ -377,13 +1312,66 @@ impl App {
     /// Suspend table updates
     fn pause(&mut self) {
         self.paused = !self.paused;
+        if let Source::Replay(replay) = &self.source
+            && !self.paused
+        {
+            // Playing again after the end starts over
+            let position = replay.position();
+            let index = if position + 1 == replay.len() {
+                0
+            } else {
+                position
+            };
+            self.show_snapshot(index);
+        }
+    }
+
+    /// True, if a recording is shown instead of the live sockets
+    pub fn is_replay(&self) -> bool {
+        matches!(self.source, Source::Replay(_))
+    }
+
+    /// Shows the next or previous snapshot of the replay and pauses it
+    fn step_replay(&mut self, forward: bool) {
+        let Source::Replay(replay) = &self.source else {
+            self.show_error("Stepping needs a recording (--replay)");
+            return;
+        };
+        let position = replay.position();
+        self.paused = true;
+        if forward {
+            self.show_snapshot(position + 1);
+        } else {
+            self.show_snapshot(position.saturating_sub(1));
+        }
+    }
+
+    fn change_replay_speed(&mut self, faster: bool) {
+        let Source::Replay(replay) = &mut self.source else {
+            self.show_error("The speed can be changed in replays (--replay)");
+            return;
+        };
+        replay.change_speed(faster);
+        let speed = replay.speed_label();
+        self.show_message(format!("Replay speed {speed}"));
     }
 
     fn scroll_up_selection(&mut self) {
         match self.ui_state {
             UiState::ConnectionTable => self.scroll_up_connections(),
-            UiState::Help => {}
+            UiState::Help => self.scroll_up_rows(1),
             UiState::ProcessInfo => self.scroll_up_process_info(),
+            UiState::ProtocolFilter
+            | UiState::UserFilter
+            | UiState::NamespaceFilter
+            | UiState::CgroupFilter
+            | UiState::ColumnChooser
+            | UiState::BulkActions
+            | UiState::CopyMenu
+            | UiState::ExportMenu
+            | UiState::ConfirmSignal => {
+                self.overlay_cursor = self.overlay_cursor.saturating_sub(1);
+            }
         }
     }
 
@@ -407,6 +1395,21 @@ impl App {
     }
 
     fn scroll_up_process_info(&mut self) {
+        match self.process_tab {
+            ProcessTab::Tree => {
+                self.process_tree_cursor = self.process_tree_cursor.saturating_sub(1);
+                return;
+            }
+            ProcessTab::Sockets => {
+                self.process_socket_cursor = self.process_socket_cursor.saturating_sub(1);
+                return;
+            }
+            ProcessTab::Files => {
+                self.process_file_cursor = self.process_file_cursor.saturating_sub(1);
+                return;
+            }
+            ProcessTab::Info | ProcessTab::Environment => {}
+        }
         let current_scroll_position = self.scroll_process_info.get();
         if current_scroll_position > 0 {
             self.scroll_process_info
@@ -417,8 +1420,20 @@ impl App {
     fn scroll_down_selection(&mut self) {
         match self.ui_state {
             UiState::ConnectionTable => self.scroll_down_connections(),
-            UiState::Help => {}
+            UiState::Help => self.scroll_down_rows(1),
             UiState::ProcessInfo => self.scroll_down_process_info(),
+            UiState::ProtocolFilter
+            | UiState::UserFilter
+            | UiState::NamespaceFilter
+            | UiState::CgroupFilter
+            | UiState::ColumnChooser
+            | UiState::BulkActions
+            | UiState::CopyMenu
+            | UiState::ExportMenu
+            | UiState::ConfirmSignal => {
+                self.overlay_cursor =
+                    (self.overlay_cursor + 1).min(self.overlay_items_len().saturating_sub(1));
+            }
         }
     }
 
@@ -442,6 +1457,24 @@ impl App {
     }
 
     fn scroll_down_process_info(&mut self) {
+        match self.process_tab {
+            ProcessTab::Tree => {
+                self.process_tree_cursor =
+                    (self.process_tree_cursor + 1).min(self.process_tree.len().saturating_sub(1));
+                return;
+            }
+            ProcessTab::Sockets => {
+                self.process_socket_cursor = (self.process_socket_cursor + 1)
+                    .min(self.process_sockets().len().saturating_sub(1));
+                return;
+            }
+            ProcessTab::Files => {
+                self.process_file_cursor =
+                    (self.process_file_cursor + 1).min(self.process_files.len().saturating_sub(1));
+                return;
+            }
+            ProcessTab::Info | ProcessTab::Environment => {}
+        }
         let current_scroll_position = self.scroll_process_info.get();
         if current_scroll_position
             < self
@@ -453,60 +1486,136 @@ impl App {
         }
     }
 
-    fn scroll_up_page(&mut self) {
+    /// Selects the connection at `index` of the table
+    fn select_connection(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get(index) {
+            self.selected = Some(entry.clone());
+            self.selected_index = Some(index);
+        }
+    }
+
+    /// Rows scrolled by a page
+    fn page_rows(&self) -> usize {
+        self.visible_table_height.get().max(1)
+    }
+
+    /// Moves the selection, cursor or scroll position up by `rows`, saturating at the first line
+    fn scroll_up_rows(&mut self, rows: usize) {
         match self.ui_state {
-            UiState::ConnectionTable => self.scroll_up_connections_page(),
-            UiState::Help => {}
-            UiState::ProcessInfo => self.scroll_up_process_info_page(),
+            UiState::ConnectionTable => self.scroll_up_connections_rows(rows),
+            UiState::Help => self
+                .scroll_help
+                .set(self.scroll_help.get().saturating_sub(rows)),
+            UiState::ProcessInfo => self.scroll_up_process_info_rows(rows),
+            UiState::ProtocolFilter
+            | UiState::UserFilter
+            | UiState::NamespaceFilter
+            | UiState::CgroupFilter
+            | UiState::ColumnChooser
+            | UiState::BulkActions
+            | UiState::CopyMenu
+            | UiState::ExportMenu
+            | UiState::ConfirmSignal => {
+                self.overlay_cursor = self.overlay_cursor.saturating_sub(rows);
+            }
         }
     }
 
-    fn scroll_down_page(&mut self) {
+    /// Moves the selection, cursor or scroll position down by `rows`, saturating at the last line
+    fn scroll_down_rows(&mut self, rows: usize) {
         match self.ui_state {
-            UiState::ConnectionTable => self.scroll_down_connections_page(),
-            UiState::Help => {}
-            UiState::ProcessInfo => self.scroll_down_process_info_page(),
+            UiState::ConnectionTable => self.scroll_down_connections_rows(rows),
+            // The help overlay limits the position to its length when rendering
+            UiState::Help => self
+                .scroll_help
+                .set(self.scroll_help.get().saturating_add(rows)),
+            UiState::ProcessInfo => self.scroll_down_process_info_rows(rows),
+            UiState::ProtocolFilter
+            | UiState::UserFilter
+            | UiState::NamespaceFilter
+            | UiState::CgroupFilter
+            | UiState::ColumnChooser
+            | UiState::BulkActions
+            | UiState::CopyMenu
+            | UiState::ExportMenu
+            | UiState::ConfirmSignal => {
+                self.overlay_cursor = self
+                    .overlay_cursor
+                    .saturating_add(rows)
+                    .min(self.overlay_items_len().saturating_sub(1));
+            }
         }
     }
 
-    fn scroll_up_connections_page(&mut self) {
+    fn scroll_up_connections_rows(&mut self, rows: usize) {
         if self.entries.is_empty() {
             return;
         }
-        let page = self.visible_table_height.get().max(1);
         let current = self.selected_index.unwrap_or(0);
-        let new_index = current.saturating_sub(page);
+        let new_index = current.saturating_sub(rows);
         self.selected = Some(self.entries[new_index].clone());
         self.selected_index = Some(new_index);
     }
 
-    fn scroll_down_connections_page(&mut self) {
+    fn scroll_down_connections_rows(&mut self, rows: usize) {
         if self.entries.is_empty() {
             return;
         }
-        let page = self.visible_table_height.get().max(1);
         let len = self.entries.len();
         let current = self.selected_index.unwrap_or(0);
-        let new_index = (current + page).min(len.saturating_sub(1));
+        let new_index = current.saturating_add(rows).min(len.saturating_sub(1));
         self.selected = Some(self.entries[new_index].clone());
         self.selected_index = Some(new_index);
     }
 
-    fn scroll_up_process_info_page(&mut self) {
-        let page = self.visible_table_height.get().max(1);
-        let current = self.scroll_process_info.get();
-        self.scroll_process_info.set(current.saturating_sub(page));
+    fn scroll_up_process_info_rows(&mut self, rows: usize) {
+        match self.process_tab {
+            ProcessTab::Tree => {
+                self.process_tree_cursor = self.process_tree_cursor.saturating_sub(rows)
+            }
+            ProcessTab::Sockets => {
+                self.process_socket_cursor = self.process_socket_cursor.saturating_sub(rows)
+            }
+            ProcessTab::Files => {
+                self.process_file_cursor = self.process_file_cursor.saturating_sub(rows)
+            }
+            ProcessTab::Info | ProcessTab::Environment => {
+                let current = self.scroll_process_info.get();
+                self.scroll_process_info.set(current.saturating_sub(rows));
+            }
+        }
     }
 
-    fn scroll_down_process_info_page(&mut self) {
-        let current = self.scroll_process_info.get();
-        let max_scroll = self
-            .process_info_list_length
-            .get()
-            .saturating_sub(self.visible_table_height.get());
-        let page = self.visible_table_height.get().max(1);
-        self.scroll_process_info
-            .set((current + page).min(max_scroll));
+    fn scroll_down_process_info_rows(&mut self, rows: usize) {
+        match self.process_tab {
+            ProcessTab::Tree => {
+                self.process_tree_cursor = self
+                    .process_tree_cursor
+                    .saturating_add(rows)
+                    .min(self.process_tree.len().saturating_sub(1));
+            }
+            ProcessTab::Sockets => {
+                self.process_socket_cursor = self
+                    .process_socket_cursor
+                    .saturating_add(rows)
+                    .min(self.process_sockets().len().saturating_sub(1));
+            }
+            ProcessTab::Files => {
+                self.process_file_cursor = self
+                    .process_file_cursor
+                    .saturating_add(rows)
+                    .min(self.process_files.len().saturating_sub(1));
+            }
+            ProcessTab::Info | ProcessTab::Environment => {
+                let current = self.scroll_process_info.get();
+                let max_scroll = self
+                    .process_info_list_length
+                    .get()
+                    .saturating_sub(self.visible_table_height.get());
+                self.scroll_process_info
+                    .set(current.saturating_add(rows).min(max_scroll));
+            }
+        }
     }
 
     fn toggle_ip_version(&mut self) {
@@ -518,41 +1627,1194 @@ impl App {
         self.refresh_connection_list();
     }
 
-    fn toggle_proto_version(&mut self) {
-        self.protocol_filter = match self.protocol_filter {
-            ProtocolFilter::TcpOnly => ProtocolFilter::UdpOnly,
-            ProtocolFilter::UdpOnly => ProtocolFilter::TcpAndUdp,
-            ProtocolFilter::TcpAndUdp => ProtocolFilter::TcpOnly,
-        };
-        self.refresh_connection_list();
+    /// Columns of the connection table in display order
+    pub fn visible_columns(&self) -> Vec<SortColumn> {
+        self.available_columns()
+            .into_iter()
+            .filter(|layout| layout.visible)
+            .map(|layout| layout.column)
+            .collect()
     }
 
-    fn toggle_dns_resolution(&mut self) {
-        self.resolve_address_names = !self.resolve_address_names;
-        self.refresh_connection_list();
+    /// Layout of the columns that have content on this system, shown or not
+    pub fn available_columns(&self) -> Vec<ColumnLayout> {
+        self.config
+            .columns
+            .iter()
+            .filter(|layout| match layout.column {
+                SortColumn::Namespace => self.all_namespaces,
+                SortColumn::Cgroup => cfg!(target_os = "linux"),
+                _ => true,
+            })
+            .copied()
+            .collect()
     }
 
-    fn sort_by_column(&mut self, sort_column: SortColumn) {
-        if self.sort_column == sort_column {
-            self.sort_order = match self.sort_order {
-                SortOrder::Asc => SortOrder::Desc,
-                SortOrder::Desc => SortOrder::Asc,
+    /// Width needed to show the header and the widest value of `column`, limited to its
+    /// maximum width
+    pub fn content_width(&self, column: SortColumn) -> u16 {
+        // Room for the sort arrow and priority
+        let header = column.label().width() + 3;
+        self.entries
+            .iter()
+            .map(|entry| entry.column_text(column).width())
+            .max()
+            .unwrap_or(0)
+            .max(header)
+            .min(column.width().max(header as u16) as usize) as u16
+    }
+
+    /// Open or close the column chooser overlay
+    fn toggle_column_chooser(&mut self) {
+        match self.ui_state {
+            UiState::ColumnChooser => self.quit(),
+            UiState::ConnectionTable => {
+                self.overlay_cursor = 0;
+                self.ui_state = UiState::ColumnChooser;
+            }
+            _ => {}
+        }
+    }
+
+    /// Shows a message in the status bar for a few seconds
+    pub fn show_message(&mut self, text: impl Into<String>) {
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            is_error: false,
+            since: Instant::now(),
+        });
+    }
+
+    /// Shows an error in the status bar for a few seconds
+    pub fn show_error(&mut self, text: impl Into<String>) {
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            is_error: true,
+            since: Instant::now(),
+        });
+    }
+
+    /// Writes the config file; a failure is reported in the status bar. Returns true if saved.
+    fn save_config(&mut self) -> bool {
+        if let Some(error) = &self.config_error {
+            self.show_error(format!("Config not saved, the file has errors: {error}"));
+            return false;
+        }
+        match self.config.save() {
+            Ok(()) => true,
+            Err(error) => {
+                self.show_error(format!("Config not saved: {error}"));
+                false
+            }
+        }
+    }
+
+    /// Switch between one and two lines per connection
+    fn toggle_compact_rows(&mut self) {
+        if self.ui_state == UiState::ConnectionTable {
+            self.config.compact_rows = !self.config.compact_rows;
+            self.show_message(if self.config.compact_rows {
+                "Two lines per connection"
+            } else {
+                "One line per connection"
+            });
+            // The mode still applies to this session if it cannot be saved
+            self.save_config();
+        }
+    }
+
+    /// Switch to the next built-in or user theme; colors stay off if `NO_COLOR` is set.
+    fn next_theme(&mut self) {
+        if theme::no_color() {
+            self.show_message("Colors are disabled by NO_COLOR");
+            return;
+        }
+        let names = self.config.theme_names();
+        let current = self
+            .config
+            .theme
+            .as_ref()
+            .and_then(|theme| names.iter().position(|name| name == theme))
+            .unwrap_or(0);
+        self.config.theme = Some(names[(current + 1) % names.len()].clone());
+        self.theme = self.config.active_theme();
+        self.show_message(format!("Theme: {}", names[(current + 1) % names.len()]));
+        // The theme still applies to this session if it cannot be saved
+        self.save_config();
+    }
+
+    /// Index into the configured columns of the highlighted line of the column chooser
+    fn chooser_column_index(&self, line: usize) -> Option<usize> {
+        let column = self.available_columns().get(line)?.column;
+        self.config
+            .columns
+            .iter()
+            .position(|layout| layout.column == column)
+    }
+
+    /// Shows or hides the highlighted column; the last shown column cannot be hidden.
+    fn toggle_column_visibility(&mut self) {
+        let Some(index) = self.chooser_column_index(self.overlay_cursor) else {
+            return;
+        };
+        if self.config.columns[index].visible && self.visible_columns().len() == 1 {
+            return;
+        }
+        self.config.columns[index].visible = !self.config.columns[index].visible;
+    }
+
+    /// Moves the highlighted column one position up (left in the table) or down (right).
+    fn move_column(&mut self, up: bool) {
+        let target = if up {
+            self.overlay_cursor.checked_sub(1)
+        } else {
+            Some(self.overlay_cursor + 1)
+        };
+        let (Some(from), Some(to)) = (
+            self.chooser_column_index(self.overlay_cursor),
+            target.and_then(|line| self.chooser_column_index(line)),
+        ) else {
+            return;
+        };
+        self.config.columns.swap(from, to);
+        self.overlay_cursor = target.unwrap_or(self.overlay_cursor);
+    }
+
+    /// Changes the width of the highlighted column by `delta`, or makes it fit its content
+    /// again if `delta` is `None`.
+    fn resize_column(&mut self, delta: Option<i16>) {
+        let Some(index) = self.chooser_column_index(self.overlay_cursor) else {
+            return;
+        };
+        let layout = self.config.columns[index];
+        self.config.columns[index].width = delta.map(|delta| {
+            layout
+                .width
+                .unwrap_or_else(|| self.content_width(layout.column))
+                .saturating_add_signed(delta)
+                .clamp(3, 100)
+        });
+    }
+
+    /// Open or close the protocol selection overlay
+    fn toggle_proto_version(&mut self) {
+        match self.ui_state {
+            UiState::ProtocolFilter => self.quit(),
+            UiState::ConnectionTable => {
+                self.overlay_cursor = 0;
+                self.ui_state = UiState::ProtocolFilter;
+            }
+            _ => {}
+        }
+    }
+
+    /// Open or close the user selection overlay
+    fn toggle_user_filter(&mut self) {
+        match self.ui_state {
+            UiState::UserFilter => self.quit(),
+            UiState::ConnectionTable => {
+                self.overlay_cursor = self
+                    .user_filter_options()
+                    .iter()
+                    .position(|option| *option == self.user_filter)
+                    .unwrap_or(0);
+                self.ui_state = UiState::UserFilter;
+            }
+            _ => {}
+        }
+    }
+
+    /// Open or close the network namespace selection overlay
+    fn toggle_namespace_filter(&mut self) {
+        match self.ui_state {
+            UiState::NamespaceFilter => self.quit(),
+            UiState::ConnectionTable if self.all_namespaces => {
+                self.overlay_cursor = self
+                    .namespace_filter_options()
+                    .iter()
+                    .position(|option| *option == self.namespace_filter)
+                    .unwrap_or(0);
+                self.ui_state = UiState::NamespaceFilter;
+            }
+            _ => {}
+        }
+    }
+
+    /// Choices of the network namespace selection overlay; `None` shows all namespaces
+    pub fn namespace_filter_options(&self) -> Vec<Option<String>> {
+        let mut options = vec![None];
+        options.extend(self.known_namespaces.iter().cloned().map(Some));
+        if let Some(namespace) = &self.namespace_filter
+            && !self.known_namespaces.contains(namespace)
+        {
+            options.push(self.namespace_filter.clone());
+        }
+        options
+    }
+
+    /// Open or close the unit/container selection overlay
+    fn toggle_cgroup_filter(&mut self) {
+        match self.ui_state {
+            UiState::CgroupFilter => self.quit(),
+            UiState::ConnectionTable => {
+                self.overlay_cursor = self
+                    .cgroup_filter_options()
+                    .iter()
+                    .position(|option| *option == self.cgroup_filter)
+                    .unwrap_or(0);
+                self.ui_state = UiState::CgroupFilter;
+            }
+            _ => {}
+        }
+    }
+
+    /// Choices of the unit/container selection overlay; `None` shows all
+    pub fn cgroup_filter_options(&self) -> Vec<Option<String>> {
+        let mut options = vec![None];
+        options.extend(self.known_cgroups.iter().cloned().map(Some));
+        if let Some(cgroup) = &self.cgroup_filter
+            && !self.known_cgroups.contains(cgroup)
+        {
+            options.push(self.cgroup_filter.clone());
+        }
+        options
+    }
+
+    /// Choices of the user selection overlay
+    pub fn user_filter_options(&self) -> Vec<UserFilter> {
+        let mut options = vec![UserFilter::AllUsers, UserFilter::CurrentUser];
+        options.extend(self.known_users.iter().cloned().map(UserFilter::User));
+        if let UserFilter::User(user) = &self.user_filter
+            && !self.known_users.contains(user)
+        {
+            options.push(self.user_filter.clone());
+        }
+        options
+    }
+
+    /// True, if a selection overlay is open
+    fn is_selection_overlay(&self) -> bool {
+        matches!(
+            self.ui_state,
+            UiState::ProtocolFilter
+                | UiState::UserFilter
+                | UiState::NamespaceFilter
+                | UiState::CgroupFilter
+                | UiState::ColumnChooser
+                | UiState::BulkActions
+                | UiState::CopyMenu
+                | UiState::ExportMenu
+                | UiState::ConfirmSignal
+        )
+    }
+
+    /// Number of lines of the open selection overlay
+    fn overlay_items_len(&self) -> usize {
+        match self.ui_state {
+            UiState::ProtocolFilter => Protocol::ALL.len(),
+            UiState::UserFilter => self.user_filter_options().len(),
+            UiState::NamespaceFilter => self.namespace_filter_options().len(),
+            UiState::CgroupFilter => self.cgroup_filter_options().len(),
+            UiState::ColumnChooser => self.available_columns().len(),
+            UiState::BulkActions => BulkAction::ALL.len(),
+            UiState::CopyMenu => CopyField::ALL.len(),
+            UiState::ExportMenu => ExportFormat::ALL.len(),
+            UiState::ConfirmSignal => self
+                .pending_signal
+                .as_ref()
+                .map_or(0, |pending| pending.processes.len()),
+            _ => 0,
+        }
+    }
+
+    /// Toggle or choose the highlighted line of the open selection overlay
+    fn select_item(&mut self) {
+        match self.ui_state {
+            UiState::ProtocolFilter => {
+                if let Some(protocol) = Protocol::ALL.get(self.overlay_cursor) {
+                    self.protocol_filter.toggle(*protocol);
+                }
+            }
+            UiState::UserFilter => {
+                if let Some(option) = self.user_filter_options().get(self.overlay_cursor) {
+                    self.user_filter = option.clone();
+                }
+                self.quit();
+            }
+            UiState::NamespaceFilter => {
+                if let Some(option) = self.namespace_filter_options().get(self.overlay_cursor) {
+                    self.namespace_filter = option.clone();
+                }
+                self.quit();
+            }
+            UiState::CgroupFilter => {
+                if let Some(option) = self.cgroup_filter_options().get(self.overlay_cursor) {
+                    self.cgroup_filter = option.clone();
+                }
+                self.quit();
+            }
+            UiState::ColumnChooser => self.toggle_column_visibility(),
+            UiState::BulkActions => {
+                let action = BulkAction::ALL.get(self.overlay_cursor).copied();
+                self.quit();
+                if let Some(action) = action {
+                    self.run_bulk_action(action);
+                }
+            }
+            UiState::CopyMenu => {
+                let field = CopyField::ALL.get(self.overlay_cursor).copied();
+                self.quit();
+                if let Some(field) = field {
+                    self.copy_selection(field.template());
+                }
+            }
+            UiState::ExportMenu => {
+                let format = ExportFormat::ALL.get(self.overlay_cursor).copied();
+                self.quit();
+                if let Some(format) = format {
+                    self.export_entries(format);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_dns_resolution(&mut self) {
+        self.resolve_address_names = !self.resolve_address_names;
+        self.refresh_connection_list();
+    }
+
+    /// Marks or unmarks the selected connection and selects the next one
+    fn toggle_mark(&mut self) {
+        if self.ui_state != UiState::ConnectionTable {
+            return;
+        }
+        if self.selected.is_none() {
+            self.select_connection(0);
+        }
+        let Some(id) = self.selected.as_ref().map(ConnectionEntry::id) else {
+            return;
+        };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+        self.scroll_down_connections();
+    }
+
+    /// Starts a range at the selection, or marks all rows of the started range
+    fn toggle_visual_mark(&mut self) {
+        if self.ui_state != UiState::ConnectionTable {
+            return;
+        }
+        if let Some(range) = self.visual_range() {
+            let count = range.clone().count();
+            let ids: Vec<ConnectionId> = self.entries[range]
+                .iter()
+                .map(ConnectionEntry::id)
+                .collect();
+            self.marked.extend(ids);
+            self.visual_anchor = None;
+            self.show_message(format!("Marked {count} rows"));
+            return;
+        }
+        if self.selected.is_none() {
+            self.select_connection(0);
+        }
+        self.visual_anchor = self.selected.as_ref().map(ConnectionEntry::id);
+        if self.visual_anchor.is_some() {
+            self.show_message("Move the selection to extend the range, V marks it, Esc cancels");
+        }
+    }
+
+    fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.visual_anchor = None;
+        self.show_message("Marks cleared");
+    }
+
+    /// Rows from the start of the visual range to the selection
+    pub fn visual_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor.as_ref()?;
+        let start = self
+            .entries
+            .iter()
+            .position(|entry| entry.id() == *anchor)?;
+        let end = self.selected_index?;
+        Some(start.min(end)..=start.max(end))
+    }
+
+    /// Shown connections that are marked or inside the visual range
+    pub fn marked_entries(&self) -> Vec<&ConnectionEntry> {
+        let range = self.visual_range();
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(index, entry)| {
+                range.as_ref().is_some_and(|range| range.contains(index))
+                    || self.marked.contains(&entry.id())
+            })
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// Connections bulk actions apply to: the marked ones, or else the selected one
+    fn bulk_targets(&self) -> Vec<&ConnectionEntry> {
+        let marked = self.marked_entries();
+        if !marked.is_empty() {
+            return marked;
+        }
+        self.selected_index
+            .and_then(|index| self.entries.get(index))
+            .into_iter()
+            .collect()
+    }
+
+    /// Open or close the bulk action menu
+    fn toggle_bulk_actions(&mut self) {
+        match self.ui_state {
+            UiState::BulkActions => self.quit(),
+            UiState::ConnectionTable if self.bulk_targets().is_empty() => {
+                self.show_error("No marked or selected connection");
+            }
+            UiState::ConnectionTable => {
+                self.overlay_cursor = 0;
+                self.ui_state = UiState::BulkActions;
+            }
+            _ => {}
+        }
+    }
+
+    fn run_bulk_action(&mut self, action: BulkAction) {
+        let targets = self.bulk_targets();
+        let count = targets.len();
+        if let Some((signal, name)) = action.signal() {
+            if self.is_replay() {
+                self.show_error("Recorded processes cannot be signalled");
+                return;
+            }
+            let pids: BTreeSet<u32> = targets
+                .iter()
+                .filter(|entry| !entry.closed)
+                .flat_map(|entry| entry.pids.iter().copied())
+                .filter(|pid| *pid != 0)
+                .collect();
+            self.ask_to_signal(&pids, signal, name);
+            return;
+        }
+        // Tab separated fields of the shown columns
+        let columns = self.visible_columns();
+        let mut lines: Vec<String> = targets
+            .iter()
+            .map(|entry| {
+                columns
+                    .iter()
+                    .map(|column| entry.column_text(*column))
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect();
+        if action == BulkAction::Copy {
+            match clipboard::copy(&lines.join("\n")) {
+                Ok(()) => self.show_message(format!("Copied {count} rows to the clipboard")),
+                Err(error) => self.show_error(format!("Copying failed: {error}")),
+            }
+            return;
+        }
+        let header = columns
+            .iter()
+            .map(|column| column.label())
+            .collect::<Vec<_>>();
+        lines.insert(0, header.join("\t"));
+        lines.push(String::new());
+        let written = export::create_file("netview-marked", "tsv").and_then(|(mut file, path)| {
+            file.write_all(lines.join("\n").as_bytes())?;
+            Ok(path)
+        });
+        match written {
+            Ok(path) => self.show_message(format!("Wrote {count} rows to {}", path.display())),
+            Err(error) => self.show_error(format!("Writing rows failed: {error}")),
+        }
+    }
+
+    /// Shows the processes `pids` that `signal` would be sent to and asks for confirmation.
+    /// PID 1 and netview itself are never signalled.
+    fn ask_to_signal(&mut self, pids: &BTreeSet<u32>, signal: Signal, name: &'static str) {
+        let own_pid = std::process::id();
+        let (skipped, pids): (Vec<u32>, Vec<u32>) =
+            pids.iter().partition(|pid| **pid == 1 || **pid == own_pid);
+        if pids.is_empty() {
+            self.show_error(if skipped.is_empty() {
+                "No owning process known".to_string()
+            } else {
+                format!("Not sending {name} to PID 1 or netview")
+            });
+            return;
+        }
+        let sysinfo_pids: Vec<Pid> = pids.iter().copied().map(Pid::from_u32).collect();
+        self.process_system
+            .refresh_processes(sysinfo::ProcessesToUpdate::Some(&sysinfo_pids), true);
+        let processes = pids
+            .into_iter()
+            .map(
+                |pid| match self.process_system.process(Pid::from_u32(pid)) {
+                    Some(process) => (
+                        pid,
+                        process.name().to_string_lossy().into_owned(),
+                        Some(process.start_time()),
+                    ),
+                    None => (pid, "(exited)".to_string(), None),
+                },
+            )
+            .collect();
+        self.pending_signal = Some(PendingSignal {
+            signal,
+            name,
+            processes,
+            skipped,
+        });
+        self.overlay_cursor = 0;
+        self.ui_state = UiState::ConfirmSignal;
+    }
+
+    /// Sends the confirmed signal; the result is reported in the status bar
+    fn confirm_signal(&mut self) {
+        if self.ui_state != UiState::ConfirmSignal {
+            return;
+        }
+        self.ui_state = UiState::ConnectionTable;
+        let Some(pending) = self.pending_signal.take() else {
+            return;
+        };
+        let pids: Vec<Pid> = pending
+            .processes
+            .iter()
+            .map(|(pid, _, _)| Pid::from_u32(*pid))
+            .collect();
+        self.process_system
+            .refresh_processes(sysinfo::ProcessesToUpdate::Some(&pids), true);
+        let (mut sent, mut failed, mut gone) = (0, 0, 0);
+        for (pid, process_name, start_time) in &pending.processes {
+            // The PID may have been reused by another process while the dialog was open
+            let process = self
+                .process_system
+                .process(Pid::from_u32(*pid))
+                .filter(|process| {
+                    Some(process.start_time()) == *start_time
+                        && process.name().to_string_lossy() == process_name.as_str()
+                });
+            match process.map(|process| process.kill_with(pending.signal)) {
+                None => gone += 1,
+                Some(Some(true)) => sent += 1,
+                Some(_) => failed += 1,
+            }
+        }
+        let name = pending.name;
+        let mut message = format!("Sent {name} to {sent} processes");
+        if failed > 0 {
+            message.push_str(&format!(", failed for {failed}"));
+        }
+        if gone > 0 {
+            message.push_str(&format!(", skipped {gone} that exited or were replaced"));
+        }
+        if failed == 0 && gone == 0 {
+            self.show_message(message);
+        } else {
+            self.show_error(message);
+        }
+    }
+
+    /// Open or close the menu of fields to copy
+    fn toggle_copy_menu(&mut self) {
+        match self.ui_state {
+            UiState::CopyMenu => self.quit(),
+            UiState::ConnectionTable if self.selected.is_none() => {
+                self.show_error("No connection selected");
+            }
+            UiState::ConnectionTable => {
+                self.overlay_cursor = 0;
+                self.ui_state = UiState::CopyMenu;
+            }
+            _ => {}
+        }
+    }
+
+    /// Copies the selected connection, formatted by `template` or else the configured template
+    fn copy_selection(&mut self, template: Option<&str>) {
+        let Some(entry) = self.selected.clone() else {
+            self.show_error("No connection selected");
+            return;
+        };
+        let template = template.unwrap_or(self.config.copy_template()).to_string();
+        let text = self.expand_copy_template(&template, &entry);
+        match clipboard::copy(&text) {
+            Ok(()) => self.show_message(format!("Copied {text}")),
+            Err(error) => self.show_error(format!("Copying failed: {error}")),
+        }
+    }
+
+    /// Replaces the placeholders of a copy template by the fields of `entry`
+    fn expand_copy_template(&mut self, template: &str, entry: &ConnectionEntry) -> String {
+        let command_line = if template.contains("{cmdline}") {
+            self.command_line(entry.pid)
+        } else {
+            String::new()
+        };
+        clipboard::expand_template(template, |name| {
+            Some(match name {
+                "proto" => entry.proto.clone(),
+                "local_ip" => entry.local_ip.clone(),
+                "local_port" => entry.local_port.to_string(),
+                "local" => entry.endpoint(SortColumn::LocalIP, SortColumn::LocalPort),
+                "remote_ip" => entry.remote_ip.clone(),
+                "remote_port" => entry.column_text(SortColumn::RemotePort),
+                "remote" => entry.endpoint(SortColumn::RemoteIP, SortColumn::RemotePort),
+                "state" => entry.state.clone(),
+                "pid" => entry.pid.to_string(),
+                "pids" => entry
+                    .pids
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                "process" => entry.process.clone(),
+                "user" => entry.user.clone(),
+                "namespace" => entry.namespace.clone(),
+                "cgroup" => entry.cgroup.clone(),
+                "rate" => entry.data_rate.clone(),
+                "cmdline" => command_line.clone(),
+                _ => return None,
+            })
+        })
+    }
+
+    /// Command line of process `pid`, empty if unknown
+    fn command_line(&mut self, pid: u32) -> String {
+        if let Source::Replay(replay) = &self.source {
+            return replay
+                .process(replay.position(), pid)
+                .map(|process| process.cmdline.clone())
+                .unwrap_or_default();
+        }
+        let pid = Pid::from_u32(pid);
+        self.process_system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cmd(sysinfo::UpdateKind::Always),
+        );
+        self.process_system
+            .process(pid)
+            .map(|process| {
+                process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default()
+    }
+
+    /// Open or close the export format selection
+    fn toggle_export_menu(&mut self) {
+        match self.ui_state {
+            UiState::ExportMenu => self.quit(),
+            UiState::ConnectionTable => {
+                self.overlay_cursor = 0;
+                self.ui_state = UiState::ExportMenu;
             }
+            _ => {}
+        }
+    }
+
+    /// Writes the shown connections in their current order to a file
+    fn export_entries(&mut self, format: ExportFormat) {
+        match export::write(&self.entries, format) {
+            Ok(path) => self.show_message(format!(
+                "Exported {} connections to {}",
+                self.entries.len(),
+                path.display()
+            )),
+            Err(error) => self.show_error(format!("Export failed: {error}")),
+        }
+    }
+
+    /// Keep the current row order during refreshes, or sort again
+    fn toggle_freeze_order(&mut self) {
+        self.freeze_order = !self.freeze_order;
+        if self.freeze_order {
+            self.show_message("Row order frozen, closed connections are kept");
+        } else {
+            self.show_message("Rows are sorted again");
+            self.sort_entries_by_column();
+            self.reconcile_selection_after_refresh();
+        }
+    }
+
+    /// Switch between sorting addresses numerically and by resolved name
+    fn toggle_address_sort(&mut self) {
+        self.sort_addresses_by_name = !self.sort_addresses_by_name;
+        self.show_message(
+            match (self.sort_addresses_by_name, self.resolve_address_names) {
+                (true, true) => "Sorting addresses by name",
+                (true, false) => "Sorting addresses by name once DNS resolution is on",
+                (false, _) => "Sorting addresses numerically",
+            },
+        );
+        self.sort_entries_by_column();
+        self.reconcile_selection_after_refresh();
+    }
+
+    /// Sorts by `sort_column` only, or reverses the order if already sorted by it
+    fn sort_by_column(&mut self, sort_column: SortColumn) {
+        if self.sort_column == sort_column {
+            self.sort_order = self.sort_order.reversed();
         } else {
             self.sort_column = sort_column;
         }
+        self.then_sort_by.clear();
+        self.sort_entries_by_column();
+        self.reconcile_selection_after_refresh();
+    }
+
+    /// Adds `sort_column` as the key with the lowest priority, or reverses its order if it
+    /// already is a sort key
+    fn then_sort_by_column(&mut self, sort_column: SortColumn) {
+        if self.sort_column == sort_column {
+            self.sort_order = self.sort_order.reversed();
+        } else if let Some((_, order)) = self
+            .then_sort_by
+            .iter_mut()
+            .find(|(column, _)| *column == sort_column)
+        {
+            *order = order.reversed();
+        } else {
+            self.then_sort_by.push((sort_column, SortOrder::Asc));
+        }
         self.sort_entries_by_column();
+        self.reconcile_selection_after_refresh();
+    }
+
+    /// The sort column followed by the secondary sort keys
+    pub fn sort_keys(&self) -> Vec<(SortColumn, SortOrder)> {
+        let mut keys = vec![(self.sort_column, self.sort_order)];
+        keys.extend(self.then_sort_by.iter().copied());
+        keys
     }
 
     fn show_help(&mut self) {
         self.ui_state = match self.ui_state {
             UiState::Help => UiState::ConnectionTable,
-            _ => UiState::Help,
+            _ => {
+                self.scroll_help.set(0);
+                UiState::Help
+            }
         };
     }
 
     fn show_process_info(&mut self) {
-        self.ui_state = UiState::ProcessInfo;
+        match self.ui_state {
+            UiState::ProcessInfo if self.process_tab == ProcessTab::Tree => {
+                // Navigate to the highlighted process of the tree
+                if let Some(node) = self.process_tree.get(self.process_tree_cursor) {
+                    self.open_process_info(node.pid);
+                }
+            }
+            UiState::ProcessInfo if self.process_tab == ProcessTab::Sockets => {
+                // Jump back to the connection table with the highlighted socket selected
+                if let Some(entry) = self
+                    .process_sockets()
+                    .get(self.process_socket_cursor)
+                    .map(|entry| (*entry).clone())
+                {
+                    self.jump_to_connection(entry);
+                }
+            }
+            UiState::ProcessInfo if self.process_tab == ProcessTab::Files => {
+                // Jump to the connection of the highlighted socket descriptor
+                if let Some(entry) = self
+                    .process_files
+                    .get(self.process_file_cursor)
+                    .and_then(|file| self.socket_connection(file))
+                    .cloned()
+                {
+                    self.jump_to_connection(entry);
+                }
+            }
+            UiState::ProcessInfo => {}
+            _ => {
+                if let Some(pid) = self.selected.as_ref().map(|selected| selected.pid) {
+                    self.open_process_info(pid);
+                    self.process_tab = ProcessTab::Info;
+                }
+                self.ui_state = UiState::ProcessInfo;
+            }
+        }
+    }
+
+    /// Shows process `pid` in the process info view
+    fn open_process_info(&mut self, pid: u32) {
+        self.process_info_pid = Some(pid);
+        self.scroll_process_info.set(0);
+        self.process_info_cache.replace(None);
+        self.process_history.clear();
+        self.process_socket_cursor = 0;
+        self.process_file_cursor = 0;
+        self.environment_search.clear();
+        if self.is_replay() {
+            // The recorded processes may be gone or their PIDs reused, only the recorded
+            // metadata is shown
+            self.process_files.clear();
+            self.process_environment.clear();
+            self.load_process_tree(pid);
+            return;
+        }
+        self.process_system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::everything(),
+        );
+        self.users.refresh();
+        self.load_process_files(pid);
+        self.process_environment = self
+            .process_system
+            .process(sysinfo::Pid::from_u32(pid))
+            .map(|process| {
+                process
+                    .environ()
+                    .iter()
+                    .map(|variable| {
+                        let variable = variable.to_string_lossy();
+                        let (name, value) = variable.split_once('=').unwrap_or((&variable, ""));
+                        (name.to_string(), value.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.record_process_sample(pid);
+        self.load_process_tree(pid);
+    }
+
+    /// Refreshes the process shown in the process info view and the other owners of the
+    /// selected socket.
+    fn refresh_process_stats(&mut self) {
+        let Some(pid) = self.process_info_pid else {
+            return;
+        };
+        let mut pids = vec![sysinfo::Pid::from_u32(pid)];
+        if let Some(selected) = &self.selected {
+            pids.extend(selected.pids.iter().map(|pid| sysinfo::Pid::from_u32(*pid)));
+        }
+        self.process_system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::everything(),
+        );
+        self.record_process_sample(pid);
+        self.load_process_files(pid);
+        self.process_info_cache.replace(None);
+    }
+
+    /// Reads the open file descriptors of `pid`.
+    fn load_process_files(&mut self, pid: u32) {
+        #[cfg(target_os = "linux")]
+        {
+            self.process_files = procfs::read_fds(pid)
+                .into_iter()
+                .map(|(fd, target)| ProcessFile::new(fd, target))
+                .collect();
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = pid;
+        self.process_file_cursor = self
+            .process_file_cursor
+            .min(self.process_files.len().saturating_sub(1));
+    }
+
+    /// The shown connection of a socket descriptor of the process info view
+    pub fn socket_connection(&self, file: &ProcessFile) -> Option<&ConnectionEntry> {
+        let inode = file.socket_inode?;
+        self.entries.iter().find(|entry| entry.inode == inode)
+    }
+
+    /// Leaves the process info view and selects `entry` in the connection table.
+    fn jump_to_connection(&mut self, entry: ConnectionEntry) {
+        self.selected_index = self.entries.iter().position(|e| *e == entry);
+        self.selected = Some(entry);
+        self.quit();
+    }
+
+    /// Appends the current CPU and memory usage of `pid` to the trend history.
+    fn record_process_sample(&mut self, pid: u32) {
+        /// Number of samples shown in the trend charts
+        const PROCESS_HISTORY_LENGTH: usize = 120;
+        if let Some(process) = self.process_system.process(sysinfo::Pid::from_u32(pid)) {
+            self.process_history
+                .push_back((process.cpu_usage(), process.memory()));
+            while self.process_history.len() > PROCESS_HISTORY_LENGTH {
+                self.process_history.pop_front();
+            }
+        }
+    }
+
+    fn next_process_tab(&mut self) {
+        if self.ui_state == UiState::ProcessInfo {
+            let index = ProcessTab::ALL
+                .iter()
+                .position(|tab| *tab == self.process_tab)
+                .unwrap_or(0);
+            self.process_tab = ProcessTab::ALL[(index + 1) % ProcessTab::ALL.len()];
+            self.scroll_process_info.set(0);
+        }
+    }
+
+    fn previous_process_tab(&mut self) {
+        if self.ui_state == UiState::ProcessInfo {
+            let index = ProcessTab::ALL
+                .iter()
+                .position(|tab| *tab == self.process_tab)
+                .unwrap_or(0);
+            self.process_tab =
+                ProcessTab::ALL[(index + ProcessTab::ALL.len() - 1) % ProcessTab::ALL.len()];
+            self.scroll_process_info.set(0);
+        }
+    }
+
+    /// Starts typing a search of the connection table or the environment tab
+    fn start_search(&mut self) {
+        match self.ui_state {
+            UiState::ConnectionTable => {
+                self.table_search.clear();
+                self.table_search_active = true;
+            }
+            UiState::ProcessInfo if self.process_tab == ProcessTab::Environment => {
+                self.environment_search_active = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Appends `c` to the search, or removes the last character if `None`. The table selection
+    /// moves to the first match from the selected row on.
+    fn search_input(&mut self, c: Option<char>) {
+        let search = if self.table_search_active {
+            &mut self.table_search
+        } else {
+            &mut self.environment_search
+        };
+        match c {
+            Some(c) => search.push(c),
+            None => {
+                search.pop();
+            }
+        }
+        if self.table_search_active {
+            let start = self.selected_index.unwrap_or(0);
+            if let Some(index) = self.find_match(start, true) {
+                self.select_connection(index);
+            }
+        } else {
+            self.scroll_process_info.set(0);
+        }
+    }
+
+    fn finish_search(&mut self) {
+        self.environment_search_active = false;
+        self.table_search_active = false;
+    }
+
+    fn cancel_search(&mut self) {
+        if self.table_search_active {
+            self.table_search_active = false;
+            self.table_search.clear();
+            return;
+        }
+        self.environment_search_active = false;
+        self.environment_search.clear();
+        self.scroll_process_info.set(0);
+    }
+
+    /// True if the IP address, host name, port or process of `entry` contains the table search
+    pub fn matches_search(&self, entry: &ConnectionEntry) -> bool {
+        if self.table_search.is_empty() || entry.closed {
+            return false;
+        }
+        let search = self.table_search.to_ascii_lowercase();
+        let contains = |text: &str| text.to_ascii_lowercase().contains(&search);
+        contains(&entry.local_ip)
+            || contains(&entry.remote_ip)
+            || contains(&entry.local_addr.to_string())
+            || entry
+                .remote_addr
+                .is_some_and(|addr| contains(&addr.to_string()))
+            || contains(&entry.local_port.to_string())
+            || (entry.remote_port != 0 && contains(&entry.remote_port.to_string()))
+            || contains(&entry.process)
+    }
+
+    /// Index of the first entry matching the search, searching forward or backward from `start`
+    /// (inclusive) and wrapping around at the ends
+    fn find_match(&self, start: usize, forward: bool) -> Option<usize> {
+        let len = self.entries.len();
+        (0..len)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % len
+                } else {
+                    (start + len - offset) % len
+                }
+            })
+            .find(|index| self.matches_search(&self.entries[*index]))
+    }
+
+    /// Selects the next or previous connection matching the table search
+    fn jump_to_match(&mut self, forward: bool) {
+        if self.ui_state != UiState::ConnectionTable || self.table_search.is_empty() {
+            return;
+        }
+        let len = self.entries.len();
+        let current = self.selected_index.unwrap_or(0);
+        let start = if forward {
+            current + 1
+        } else {
+            current + len.saturating_sub(1)
+        };
+        match self.find_match(start % len.max(1), forward) {
+            Some(index) => {
+                if forward && index <= current {
+                    self.show_message("Search hit bottom, continuing at top");
+                } else if !forward && index >= current {
+                    self.show_message("Search hit top, continuing at bottom");
+                }
+                self.select_connection(index);
+            }
+            None => self.show_error(format!("No match for {}", self.table_search)),
+        }
+    }
+
+    fn toggle_mask_secrets(&mut self) {
+        self.mask_secrets = !self.mask_secrets;
+    }
+
+    fn toggle_environment_sort(&mut self) {
+        self.environment_sort = match self.environment_sort {
+            EnvironmentSort::Name => EnvironmentSort::Value,
+            EnvironmentSort::Value => EnvironmentSort::Name,
+        };
+        self.scroll_process_info.set(0);
+    }
+
+    /// Environment variables matching the search in display order. Values of path lists are
+    /// split into their elements and secrets are masked if enabled.
+    pub fn environment_entries(&self) -> Vec<(&str, Vec<String>)> {
+        let search = self.environment_search.to_lowercase();
+        let mut variables = self
+            .process_environment
+            .iter()
+            .filter(|(name, value)| environment_matches(name, value, &search, self.mask_secrets))
+            .collect::<Vec<_>>();
+        match self.environment_sort {
+            EnvironmentSort::Name => variables.sort(),
+            // Masked values must not leak through the sort order either
+            EnvironmentSort::Value => variables.sort_by_cached_key(|(name, value)| {
+                let value = if self.mask_secrets && is_secret_name(name) {
+                    SECRET_MASK
+                } else {
+                    value
+                };
+                (value.to_string(), name.clone())
+            }),
+        }
+        variables
+            .into_iter()
+            .map(|(name, value)| {
+                let values = if self.mask_secrets && is_secret_name(name) {
+                    vec![SECRET_MASK.to_string()]
+                } else if is_path_list(name, value) {
+                    value.split(':').map(str::to_string).collect()
+                } else {
+                    vec![value.clone()]
+                };
+                (name.as_str(), values)
+            })
+            .collect()
+    }
+
+    /// Connections of the connection table owned by the process shown in the process info view;
+    /// sockets hidden by the filters are left out
+    pub fn process_sockets(&self) -> Vec<&ConnectionEntry> {
+        let Some(pid) = self.process_info_pid else {
+            return vec![];
+        };
+        self.entries
+            .iter()
+            .filter(|entry| entry.pids.contains(&pid))
+            .collect()
+    }
+
+    /// Collects the ancestors of `pid` up to the root process, `pid` itself and its children.
+    fn load_process_tree(&mut self, pid: u32) {
+        // Name and parent by PID, of the recorded processes in a replay
+        let processes: HashMap<u32, (String, Option<u32>)> = match &self.source {
+            Source::Replay(replay) => replay
+                .processes(replay.position())
+                .into_iter()
+                .map(|(pid, process)| (pid, (process.name.clone(), process.parent)))
+                .collect(),
+            Source::Live(_) => self
+                .process_system
+                .processes()
+                .iter()
+                .filter(|(_, process)| process.thread_kind().is_none())
+                .map(|(pid, process)| {
+                    (
+                        pid.as_u32(),
+                        (
+                            process.name().to_string_lossy().to_string(),
+                            process.parent().map(|parent| parent.as_u32()),
+                        ),
+                    )
+                })
+                .collect(),
+        };
+        let node = |pid: u32, depth: usize| ProcessTreeNode {
+            pid,
+            name: processes
+                .get(&pid)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_default(),
+            depth,
+            socket_count: self.pid_socket_counts.get(&pid).copied().unwrap_or(0),
+        };
+
+        let mut ancestors = vec![pid];
+        while let Some(parent) = ancestors
+            .last()
+            .and_then(|pid| processes.get(pid))
+            .and_then(|(_, parent)| *parent)
+        {
+            if ancestors.contains(&parent) {
+                break;
+            }
+            ancestors.push(parent);
+        }
+        ancestors.reverse();
+
+        let mut children = processes
+            .iter()
+            .filter(|(_, (_, parent))| *parent == Some(pid))
+            .map(|(child, _)| *child)
+            .collect::<Vec<_>>();
+        children.sort();
+
+        let depth = ancestors.len();
+        let mut tree = ancestors
+            .iter()
+            .enumerate()
+            .map(|(depth, ancestor)| node(*ancestor, depth))
+            .collect::<Vec<_>>();
+        tree.extend(children.into_iter().map(|child| node(child, depth)));
+        self.process_tree_cursor = depth - 1;
+        self.process_tree = tree;
     }
 
     fn get_connection_bytes(&self) -> HashMap<String, (u64, u64)> {
@@ -636,17 +2898,18 @@ impl App {
                     }
                 } else {
                     let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 5 && parts[0] != "State" {
-                        if let Some(local) = Self::parse_ss_addr_port(parts[3]) {
-                            let key = if is_udp {
-                                format!("{}:{}:", local.0, local.1)
-                            } else if let Some(peer) = Self::parse_ss_addr_port(parts[4]) {
-                                format!("{}:{}:{}:{}", local.0, local.1, peer.0, peer.1)
-                            } else {
-                                continue;
-                            };
-                            last_key = Some(key);
-                        }
+                    if parts.len() >= 5
+                        && parts[0] != "State"
+                        && let Some(local) = Self::parse_ss_addr_port(parts[3])
+                    {
+                        let key = if is_udp {
+                            format!("{}:{}:", local.0, local.1)
+                        } else if let Some(peer) = Self::parse_ss_addr_port(parts[4]) {
+                            format!("{}:{}:{}:{}", local.0, local.1, peer.0, peer.1)
+                        } else {
+                            continue;
+                        };
+                        last_key = Some(key);
                     }
                 }
                 i += 1;
@@ -710,16 +2973,10 @@ impl App {
         self.parse_address_macos(addr)
     }
 
-    fn calculate_rate(
-        &self,
-        conn_key: &str,
-        current_bytes: &HashMap<String, (u64, u64)>,
-        now: Instant,
-    ) -> (u64, u64, String, Instant) {
-        let (rx_bytes, tx_bytes) = current_bytes.get(conn_key).copied().unwrap_or((0, 0));
-
+    /// Display rate of a connection from its byte counters and those of the previous refresh
+    fn calculate_rate(&self, conn_key: &str, rx_bytes: u64, tx_bytes: u64, now: Instant) -> String {
         let prev_conns = self.previous_connections.borrow();
-        let rate = if let Some((prev_rx, prev_tx, prev_time)) = prev_conns.get(conn_key) {
+        if let Some((prev_rx, prev_tx, prev_time)) = prev_conns.get(conn_key) {
             let duration = now.duration_since(*prev_time).as_secs_f64();
             if duration > 0.0 {
                 let rx_rate = ((rx_bytes.saturating_sub(*prev_rx)) as f64 / duration) as u64;
@@ -731,12 +2988,10 @@ impl App {
             }
         } else {
             "0 B/s".to_string()
-        };
-
-        (rx_bytes, tx_bytes, rate, now)
+        }
     }
 
-    fn format_rate(&self, bytes_per_sec: u64) -> String {
+    pub(crate) fn format_rate(&self, bytes_per_sec: u64) -> String {
         const UNITS: &[&str] = &["B/s", "KB/s", "MB/s", "GB/s"];
         let mut rate = bytes_per_sec as f64;
         let mut unit_idx = 0;
@@ -773,106 +3028,256 @@ impl App {
     }
 
     fn update_connection_entries(&mut self) {
+        if let Source::Replay(replay) = &self.source {
+            self.show_snapshot(replay.position());
+            return;
+        }
+        let started = Instant::now();
+        let snapshot = self.collect_sockets();
+        self.record(&snapshot);
+        self.apply_snapshot(&snapshot, started);
+        self.last_refresh = Some((Local::now(), started.elapsed()));
+    }
+
+    /// Reads all sockets of the system with their owners and byte counters, before any filter
+    fn collect_sockets(&mut self) -> Snapshot {
         let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
         let proto_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
 
         let mut sys = System::new_all();
         sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        self.users.refresh();
+        let host_namespace = if self.all_namespaces { "host" } else { "" };
 
         // Get current byte counts
         let current_bytes = self.get_connection_bytes();
-        let now = Instant::now();
 
-        self.entries = vec![];
+        let mut sockets = vec![];
+        for conn in get_sockets_info(af_flags, proto_flags).unwrap_or_default() {
+            let pids = owner_pids(&conn.associated_pids);
+            let pid = pids.first().copied().unwrap_or(0);
+            #[cfg(target_os = "linux")]
+            let user = self.user_name_by_uid(conn.uid);
+            #[cfg(not(target_os = "linux"))]
+            let user = self.process_user_name(&sys, pid);
+            #[cfg(target_os = "linux")]
+            let inode = u64::from(conn.inode);
+            #[cfg(not(target_os = "linux"))]
+            let inode = 0;
+
+            let (protocol, local_addr, local_port, remote_addr, remote_port, state, bytes_key) =
+                match conn.protocol_socket_info {
+                    ProtocolSocketInfo::Tcp(tcp) => (
+                        Protocol::Tcp,
+                        tcp.local_addr,
+                        tcp.local_port,
+                        Some(tcp.remote_addr),
+                        tcp.remote_port,
+                        format!("{:?}", tcp.state),
+                        format!(
+                            "{}:{}:{}:{}",
+                            tcp.local_addr, tcp.local_port, tcp.remote_addr, tcp.remote_port
+                        ),
+                    ),
+                    ProtocolSocketInfo::Udp(udp) => (
+                        Protocol::Udp,
+                        udp.local_addr,
+                        udp.local_port,
+                        None,
+                        0,
+                        String::new(),
+                        format!("{}:{}:", udp.local_addr, udp.local_port),
+                    ),
+                };
+            let (rx_bytes, tx_bytes) = current_bytes.get(&bytes_key).copied().unwrap_or((0, 0));
+            sockets.push(SocketRecord {
+                protocol,
+                local_addr,
+                local_port,
+                remote_addr,
+                remote_port,
+                state,
+                process: process_name(&sys, pid),
+                pids,
+                user,
+                namespace: host_namespace.to_string(),
+                cgroup: String::new(),
+                inode,
+                rx_bytes,
+                tx_bytes,
+            });
+        }
 
-        if let Ok(sockets) = get_sockets_info(af_flags, proto_flags) {
-            for conn in sockets {
-                let pid = conn.associated_pids.first().copied().unwrap_or(0);
-                let proc_name = sys
-                    .process(sysinfo::Pid::from_u32(pid))
-                    .map(|p| p.name().to_string_lossy().to_string())
-                    .unwrap_or_default();
+        #[cfg(target_os = "linux")]
+        {
+            self.collect_proc_sockets(&sys, &current_bytes, host_namespace, &mut sockets);
+            assign_cgroups(&mut sockets);
+        }
+        let processes = if matches!(self.source, Source::Live(Some(_))) {
+            self.process_records(&sys, &sockets)
+        } else {
+            BTreeMap::new()
+        };
+        Snapshot {
+            time_ms: Local::now().timestamp_millis(),
+            sockets,
+            processes,
+        }
+    }
 
-                match conn.protocol_socket_info {
-                    ProtocolSocketInfo::Tcp(ref tcp) => {
-                        if self.show_connection(&conn) {
-                            let local_ip = self.ip_to_string(&tcp.local_addr);
-                            let remote_ip = self.ip_to_string(&tcp.remote_addr);
-                            let conn_key = format!(
-                                "{}:{}:{}:{}",
-                                local_ip, tcp.local_port, remote_ip, tcp.remote_port
-                            );
-
-                            let (rx_bytes, tx_bytes, data_rate, last_update) =
-                                self.calculate_rate(&conn_key, &current_bytes, now);
-
-                            self.entries.push(ConnectionEntry {
-                                proto: if tcp.local_addr.is_ipv4() {
-                                    "TCPv4".into()
-                                } else {
-                                    "TCPv6".into()
-                                },
-                                local_ip,
-                                local_port: tcp.local_port,
-                                remote_ip,
-                                remote_port: tcp.remote_port,
-                                state: format!("{:?}", tcp.state),
-                                pid,
-                                process: proc_name,
-                                creation_time: Instant::now(),
-                                rx_bytes,
-                                tx_bytes,
-                                data_rate,
-                                last_update,
-                            });
-                        }
-                    }
-                    ProtocolSocketInfo::Udp(ref udp) => {
-                        if self.show_connection(&conn) {
-                            let local_ip = self.ip_to_string(&udp.local_addr);
-                            let conn_key = format!("{}:{}:{}", local_ip, udp.local_port, "");
-
-                            let (rx_bytes, tx_bytes, data_rate, last_update) =
-                                self.calculate_rate(&conn_key, &current_bytes, now);
-
-                            self.entries.push(ConnectionEntry {
-                                proto: if udp.local_addr.is_ipv4() {
-                                    "UDPv4".into()
-                                } else {
-                                    "UDPv6".into()
-                                },
-                                local_ip,
-                                local_port: udp.local_port,
-                                remote_ip: "".into(),
-                                remote_port: 0,
-                                state: "".into(),
-                                pid,
-                                process: proc_name,
-                                creation_time: Instant::now(),
-                                rx_bytes,
-                                tx_bytes,
-                                data_rate,
-                                last_update,
-                            });
-                        }
-                    }
-                }
+    /// Metadata of the owners of `sockets` and their ancestors, for a recording
+    fn process_records(
+        &self,
+        sys: &System,
+        sockets: &[SocketRecord],
+    ) -> BTreeMap<u32, ProcessRecord> {
+        let mut processes = BTreeMap::new();
+        let mut pending: Vec<u32> = sockets
+            .iter()
+            .flat_map(|socket| socket.pids.iter().copied())
+            .collect();
+        while let Some(pid) = pending.pop() {
+            if processes.contains_key(&pid) {
+                continue;
+            }
+            let Some(process) = sys.process(Pid::from_u32(pid)) else {
+                continue;
+            };
+            let parent = process.parent().map(|parent| parent.as_u32());
+            pending.extend(parent);
+            #[cfg(target_os = "linux")]
+            let cgroup = cgroup::read_cgroup(pid)
+                .map(|cgroup| cgroup.label())
+                .unwrap_or_default();
+            #[cfg(not(target_os = "linux"))]
+            let cgroup = String::new();
+            processes.insert(
+                pid,
+                ProcessRecord {
+                    name: process.name().to_string_lossy().into_owned(),
+                    cmdline: process
+                        .cmd()
+                        .iter()
+                        .map(|arg| arg.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    exe: process
+                        .exe()
+                        .map(|exe| exe.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    user: self.process_user_name(sys, pid),
+                    parent,
+                    cgroup,
+                },
+            );
+        }
+        processes
+    }
+
+    /// Replaces the shown connections by the sockets of `snapshot`, taken at `now`, that pass
+    /// the filters, and updates the totals, the known filter choices and the rates.
+    fn apply_snapshot(&mut self, snapshot: &Snapshot, now: Instant) {
+        self.known_users.clear();
+        self.known_namespaces.clear();
+        self.known_cgroups.clear();
+        self.connection_totals.clear();
+        self.pid_socket_counts.clear();
+        let previous_entries = std::mem::take(&mut self.entries);
+        let mut first_seen = HashMap::with_capacity(snapshot.sockets.len());
+
+        for socket in &snapshot.sockets {
+            let id = ConnectionId {
+                proto: socket.protocol.display_name(&socket.local_addr),
+                local_addr: socket.local_addr,
+                local_port: socket.local_port,
+                remote_addr: socket.remote_addr,
+                remote_port: socket.remote_port,
+                pid: socket.pids.first().copied().unwrap_or(0),
+                namespace: socket.namespace.clone(),
+            };
+            let creation_time = self.first_seen.get(&id).copied().unwrap_or(now);
+            first_seen.insert(id, creation_time);
+            // Hidden protocols count, too, for the totals of the status bar
+            *self
+                .connection_totals
+                .entry(socket.protocol.label())
+                .or_default() += 1;
+            for pid in &socket.pids {
+                *self.pid_socket_counts.entry(*pid).or_default() += 1;
+            }
+            self.known_users.insert(socket.user.clone());
+            self.known_namespaces.insert(socket.namespace.clone());
+            self.known_cgroups.insert(socket.cgroup.clone());
+            if !self.show_connection(
+                socket.protocol,
+                &socket.local_addr,
+                &socket.user,
+                &socket.namespace,
+            ) || self
+                .cgroup_filter
+                .as_ref()
+                .is_some_and(|shown| *shown != socket.cgroup)
+            {
+                continue;
             }
+
+            let local_ip = self.ip_to_string(&socket.local_addr);
+            let remote_ip = match &socket.remote_addr {
+                Some(remote_addr) => self.ip_to_string(remote_addr),
+                None => String::new(),
+            };
+            let conn_key = rate_key(
+                &socket.namespace,
+                &local_ip,
+                socket.local_port,
+                &remote_ip,
+                socket.remote_port,
+            );
+            let data_rate = self.calculate_rate(&conn_key, socket.rx_bytes, socket.tx_bytes, now);
+            self.entries.push(ConnectionEntry {
+                proto: socket.protocol.display_name(&socket.local_addr),
+                local_ip,
+                local_port: socket.local_port,
+                remote_ip,
+                remote_port: socket.remote_port,
+                local_addr: socket.local_addr,
+                remote_addr: socket.remote_addr,
+                state: socket.state.clone(),
+                pid: socket.pids.first().copied().unwrap_or(0),
+                pids: socket.pids.clone(),
+                process: socket.process.clone(),
+                user: socket.user.clone(),
+                namespace: socket.namespace.clone(),
+                cgroup: socket.cgroup.clone(),
+                inode: socket.inode,
+                creation_time,
+                rx_bytes: socket.rx_bytes,
+                tx_bytes: socket.tx_bytes,
+                data_rate,
+                last_update: now,
+                closed: false,
+            });
         }
+        self.first_seen = first_seen;
+        self.known_users.remove("");
+        self.known_namespaces.remove("");
+        self.known_cgroups.remove("");
 
         self.entries.sort();
         self.entries.dedup();
-        self.sort_entries_by_column();
+        if self.freeze_order {
+            self.merge_frozen_entries(previous_entries);
+        } else {
+            self.sort_entries_by_column();
+        }
         self.reconcile_selection_after_refresh();
+        self.throughput = self.total_throughput(now);
 
         // Update previous connections for next rate calculation
         let mut prev_conns = self.previous_connections.borrow_mut();
         prev_conns.clear();
-        for entry in &self.entries {
-            let conn_key = format!(
-                "{}:{}:{}:{}",
-                entry.local_ip, entry.local_port, entry.remote_ip, entry.remote_port
-            );
+        for entry in self.entries.iter().filter(|entry| !entry.closed) {
+            let conn_key = entry.rate_key();
             prev_conns.insert(
                 conn_key,
                 (entry.rx_bytes, entry.tx_bytes, entry.last_update),
@@ -880,6 +3285,179 @@ impl App {
         }
     }
 
+    /// Shows snapshot `index` of the replay. Rates are measured against the snapshot before,
+    /// which is applied first unless it is the one shown.
+    fn show_snapshot(&mut self, index: usize) {
+        let Source::Replay(mut replay) = std::mem::take(&mut self.source) else {
+            return;
+        };
+        let index = index.min(replay.len() - 1);
+        // Going back, connections are seen first again
+        if index < replay.position() {
+            self.first_seen.clear();
+        }
+        if index > 0 && index != replay.position() + 1 {
+            self.previous_connections.borrow_mut().clear();
+            self.apply_snapshot(replay.snapshot(index - 1), replay.instant(index - 1));
+        }
+        replay.seek(index);
+        self.apply_snapshot(replay.snapshot(index), replay.instant(index));
+        self.last_refresh = Some((replay.snapshot(index).time(), Duration::ZERO));
+        self.source = Source::Replay(replay);
+    }
+
+    /// Received and sent bytes per second of all shown connections since the previous refresh
+    fn total_throughput(&self, now: Instant) -> (u64, u64) {
+        let prev_conns = self.previous_connections.borrow();
+        let (mut rx, mut tx) = (0.0, 0.0);
+        for entry in self.entries.iter().filter(|entry| !entry.closed) {
+            let conn_key = entry.rate_key();
+            if let Some((prev_rx, prev_tx, prev_time)) = prev_conns.get(&conn_key) {
+                let duration = now.duration_since(*prev_time).as_secs_f64();
+                if duration > 0.0 {
+                    rx += entry.rx_bytes.saturating_sub(*prev_rx) as f64 / duration;
+                    tx += entry.tx_bytes.saturating_sub(*prev_tx) as f64 / duration;
+                }
+            }
+        }
+        (rx as u64, tx as u64)
+    }
+
+    /// Keeps the rows of `previous` in place, updated from the new entries or marked as closed,
+    /// and appends new connections in sort order. A connection changing its state keeps its row.
+    fn merge_frozen_entries(&mut self, previous: Vec<ConnectionEntry>) {
+        let mut current: Vec<Option<ConnectionEntry>> = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let positions: HashMap<ConnectionId, usize> = previous
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| (entry.id(), position))
+            .collect();
+        let mut merged: Vec<Option<ConnectionEntry>> = vec![None; previous.len()];
+        for entry in &mut current {
+            let position = entry
+                .as_ref()
+                .and_then(|entry| positions.get(&entry.id()).copied());
+            if let Some(position) = position {
+                merged[position] = entry.take();
+            }
+        }
+        drop(positions);
+        let merged = merged.into_iter().zip(previous).map(|(entry, previous)| {
+            entry.unwrap_or_else(|| ConnectionEntry {
+                data_rate: "closed".to_string(),
+                closed: true,
+                ..previous
+            })
+        });
+        self.entries = current.into_iter().flatten().collect();
+        self.sort_entries_by_column();
+        self.entries.splice(0..0, merged);
+    }
+
+    /// Adds raw, ICMP and SCTP sockets, which `netstat2` does not report, from `/proc/net`, and
+    /// all sockets of other network namespaces from `/proc/<pid>/net` if enabled.
+    #[cfg(target_os = "linux")]
+    fn collect_proc_sockets(
+        &self,
+        sys: &System,
+        current_bytes: &HashMap<String, (u64, u64)>,
+        host_namespace: &str,
+        sockets: &mut Vec<SocketRecord>,
+    ) {
+        // Byte counters are read in netview's own namespace, so they only apply to its sockets
+        let mut sources = vec![(
+            std::path::PathBuf::from("/proc/net"),
+            host_namespace.to_string(),
+            vec![Protocol::Raw, Protocol::Icmp, Protocol::Sctp],
+            true,
+        )];
+        let mut named = vec![];
+        if self.all_namespaces {
+            let own_namespace = procfs::net_namespace_inode("self");
+            for namespace in procfs::net_namespaces() {
+                if Some(namespace.inode) == own_namespace {
+                    continue;
+                }
+                let Some(pid) = namespace.pid else {
+                    // Named namespaces without processes are read by entering them
+                    if let Some(name) = namespace.name {
+                        named.push(name);
+                    }
+                    continue;
+                };
+                let name = namespace
+                    .name
+                    .or_else(|| cgroup::read_container(pid).map(|c| c.short_name()))
+                    .unwrap_or_else(|| format!("ns:{}", namespace.inode));
+                sources.push((
+                    std::path::PathBuf::from(format!("/proc/{pid}/net")),
+                    name,
+                    Protocol::ALL.to_vec(),
+                    false,
+                ));
+            }
+        }
+
+        let mut proc_sockets = vec![];
+        for name in named {
+            for socket in procfs::read_named_namespace_sockets(&name, &Protocol::ALL) {
+                proc_sockets.push((socket, name.clone(), false));
+            }
+        }
+        for (net_dir, namespace, protocols, own) in sources {
+            for protocol in protocols {
+                for socket in procfs::read_sockets(&net_dir, protocol) {
+                    proc_sockets.push((socket, namespace.clone(), own));
+                }
+            }
+        }
+        if proc_sockets.is_empty() {
+            return;
+        }
+
+        let inode_pids = procfs::socket_inode_pids();
+        for (socket, namespace, own) in proc_sockets {
+            let pids = owner_pids(inode_pids.get(&socket.inode).map_or(&[], Vec::as_slice));
+            let pid = pids.first().copied().unwrap_or(0);
+            let (remote_addr, remote_port) = if socket.remote_addr.is_unspecified() {
+                (None, 0)
+            } else {
+                (Some(socket.remote_addr), socket.remote_port)
+            };
+            let bytes_key = format!(
+                "{}:{}:{}:{}",
+                socket.local_addr,
+                socket.local_port,
+                remote_addr.map_or(String::new(), |addr| addr.to_string()),
+                remote_port
+            );
+            let (rx_bytes, tx_bytes) = current_bytes
+                .get(&bytes_key)
+                .filter(|_| own)
+                .copied()
+                .unwrap_or((0, 0));
+            sockets.push(SocketRecord {
+                protocol: socket.protocol,
+                local_addr: socket.local_addr,
+                local_port: socket.local_port,
+                remote_addr,
+                remote_port,
+                state: socket.state,
+                process: process_name(sys, pid),
+                pids,
+                user: self.user_name_by_uid(socket.uid),
+                namespace,
+                cgroup: String::new(),
+                inode: socket.inode,
+                rx_bytes,
+                tx_bytes,
+            });
+        }
+    }
+
     /// Keeps selection in sync after the entries list has been refreshed (e.g. on tick).
     /// If the previously selected connection still exists, it remains selected; otherwise
     /// selection is moved to a valid row or cleared so the current line marker is not lost.
@@ -887,6 +3465,7 @@ impl App {
         if self.entries.is_empty() {
             self.selected = None;
             self.selected_index = None;
+            self.scroll_connection_table.set(0);
             return;
         }
         let len = self.entries.len();
@@ -927,55 +3506,116 @@ impl App {
         hostname
     }
 
+    /// Resolve the UID owning a socket to a user name
+    #[cfg(target_os = "linux")]
+    fn user_name_by_uid(&self, uid: u32) -> String {
+        sysinfo::Uid::try_from(uid as usize)
+            .ok()
+            .and_then(|uid| self.users.get_user_by_id(&uid))
+            .map_or_else(|| uid.to_string(), |user| user.name().to_string())
+    }
+
+    /// User of process `pid`; without per-socket UIDs, the owner of a socket is the user of
+    /// its process
+    fn process_user_name(&self, sys: &System, pid: u32) -> String {
+        sys.process(sysinfo::Pid::from_u32(pid))
+            .and_then(|process| process.user_id())
+            .and_then(|uid| self.users.get_user_by_id(uid))
+            .map(|user| user.name().to_string())
+            .unwrap_or_default()
+    }
+
     /// Return true, if a connection is not filtered out and shall be displayed
-    fn show_connection(&self, socket_info: &SocketInfo) -> bool {
-        match &socket_info.protocol_socket_info {
-            ProtocolSocketInfo::Tcp(tcp) => {
-                if self.protocol_filter == ProtocolFilter::UdpOnly {
-                    return false;
-                }
-                if tcp.local_addr.is_ipv4() && self.ip_version_filter == IpVersionFilter::Ipv6Only {
-                    return false;
-                }
-                if tcp.local_addr.is_ipv6() && self.ip_version_filter == IpVersionFilter::Ipv4Only {
-                    return false;
-                }
-            }
-            ProtocolSocketInfo::Udp(udp) => {
-                if self.protocol_filter == ProtocolFilter::TcpOnly {
-                    return false;
-                }
-                if udp.local_addr.is_ipv4() && self.ip_version_filter == IpVersionFilter::Ipv6Only {
-                    return false;
-                }
-                if udp.local_addr.is_ipv6() && self.ip_version_filter == IpVersionFilter::Ipv4Only {
-                    return false;
-                }
-            }
+    fn show_connection(
+        &self,
+        protocol: Protocol,
+        local_addr: &IpAddr,
+        user: &str,
+        namespace: &str,
+    ) -> bool {
+        if !self.protocol_filter.is_shown(protocol) {
+            return false;
+        }
+        if self
+            .namespace_filter
+            .as_ref()
+            .is_some_and(|shown| shown != namespace)
+        {
+            return false;
+        }
+        match &self.user_filter {
+            UserFilter::AllUsers => {}
+            UserFilter::CurrentUser if user != self.current_user => return false,
+            UserFilter::User(name) if user != name => return false,
+            _ => {}
+        }
+        if local_addr.is_ipv4() && self.ip_version_filter == IpVersionFilter::Ipv6Only {
+            return false;
+        }
+        if local_addr.is_ipv6() && self.ip_version_filter == IpVersionFilter::Ipv4Only {
+            return false;
         }
         true
     }
 
+    /// Sorts by all sort keys. Rows equal in all of them keep a fixed order, so they don't
+    /// swap places between refreshes.
     fn sort_entries_by_column(&mut self) {
+        let sort_keys = self.sort_keys();
+        // Closed rows are only kept in a frozen order
+        let mut entries = std::mem::take(&mut self.entries);
+        entries.retain(|entry| !entry.closed);
+        entries.sort_by(|a, b| {
+            sort_keys
+                .iter()
+                .map(|(column, order)| self.compare_entries(a, b, *column, *order))
+                .find(|ord| ord.is_ne())
+                .unwrap_or_else(|| a.cmp(b))
+        });
+        self.entries = entries;
+    }
+
+    /// Compares two entries by `column`; empty values are at the end in either order.
+    /// Addresses are compared numerically, or by name if chosen while DNS resolution is on.
+    fn compare_entries(
+        &self,
+        a: &ConnectionEntry,
+        b: &ConnectionEntry,
+        column: SortColumn,
+        sort_order: SortOrder,
+    ) -> Ordering {
         use SortColumn::*;
-        self.entries.sort_by(|a, b| {
-            let ord = match self.sort_column {
-                Proto => a.proto.cmp(&b.proto),
-                LocalIP => a.local_ip.cmp(&b.local_ip),
-                LocalPort => a.local_port.cmp(&b.local_port),
-                RemoteIP => string_compare_with_empty(&a.remote_ip, &b.remote_ip, self.sort_order),
-                RemotePort => remote_port_compare(a.remote_port, b.remote_port, self.sort_order),
-                State => string_compare_with_empty(&a.state, &b.state, self.sort_order),
-                PID => a.pid.cmp(&b.pid),
-                Process => string_compare_with_empty(&a.process, &b.process, self.sort_order),
-                DataRate => Self::parse_rate(&a.data_rate).cmp(&Self::parse_rate(&b.data_rate)),
-            };
-            if self.sort_order == SortOrder::Asc {
-                ord
-            } else {
-                ord.reverse()
+        let by_name = self.sort_addresses_by_name && self.resolve_address_names;
+        let ipv6_first = self.config.ipv6_first;
+        let ord = match column {
+            Proto => a.proto.cmp(&b.proto),
+            LocalIP if by_name => a.local_ip.cmp(&b.local_ip),
+            LocalIP => address_compare(Some(a.local_addr), Some(b.local_addr), ipv6_first),
+            LocalPort => a.local_port.cmp(&b.local_port),
+            RemoteIP if by_name => {
+                string_compare_with_empty(&a.remote_ip, &b.remote_ip, sort_order)
             }
-        });
+            RemoteIP => match (a.remote_addr, b.remote_addr) {
+                // Keep connections without remote end at the bottom, like empty strings
+                (None, Some(_)) | (Some(_), None) => {
+                    string_compare_with_empty(&a.remote_ip, &b.remote_ip, sort_order)
+                }
+                (a, b) => address_compare(a, b, ipv6_first),
+            },
+            RemotePort => remote_port_compare(a.remote_port, b.remote_port, sort_order),
+            State => string_compare_with_empty(&a.state, &b.state, sort_order),
+            PID => a.pid.cmp(&b.pid),
+            Process => string_compare_with_empty(&a.process, &b.process, sort_order),
+            DataRate => Self::parse_rate(&a.data_rate).cmp(&Self::parse_rate(&b.data_rate)),
+            User => string_compare_with_empty(&a.user, &b.user, sort_order),
+            Namespace => string_compare_with_empty(&a.namespace, &b.namespace, sort_order),
+            Cgroup => string_compare_with_empty(&a.cgroup, &b.cgroup, sort_order),
+        };
+        if sort_order == SortOrder::Asc {
+            ord
+        } else {
+            ord.reverse()
+        }
     }
 
     fn find_previous_entry(&self, entry: &ConnectionEntry) -> Option<&ConnectionEntry> {
@@ -999,6 +3639,79 @@ impl App {
     }
 }
 
+/// Name of the user running netview
+fn current_user_name() -> String {
+    let users = sysinfo::Users::new_with_refreshed_list();
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return String::new();
+    };
+    let mut sys = System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+    sys.process(pid)
+        .and_then(|process| process.user_id())
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|user| user.name().to_string())
+        .unwrap_or_default()
+}
+
+/// Sorted and deduplicated owner PIDs of a socket, so the first one is deterministic
+fn owner_pids(associated_pids: &[u32]) -> Vec<u32> {
+    let mut pids = associated_pids.to_vec();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// Key of the byte counters of a connection for rates. The namespace tells apart sockets with
+/// the same addresses in different network namespaces.
+fn rate_key(
+    namespace: &str,
+    local_ip: &str,
+    local_port: u16,
+    remote_ip: &str,
+    remote_port: u16,
+) -> String {
+    format!("{namespace}/{local_ip}:{local_port}:{remote_ip}:{remote_port}")
+}
+
+/// Name of process `pid`, empty if it is unknown
+fn process_name(sys: &System, pid: u32) -> String {
+    sys.process(sysinfo::Pid::from_u32(pid))
+        .map(|process| process.name().to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Fills in the unit/container of the owning process of each socket
+#[cfg(target_os = "linux")]
+fn assign_cgroups(sockets: &mut [SocketRecord]) {
+    let mut labels: HashMap<u32, String> = HashMap::new();
+    for socket in sockets {
+        let Some(&pid) = socket.pids.first() else {
+            continue;
+        };
+        socket.cgroup = labels
+            .entry(pid)
+            .or_insert_with(|| {
+                cgroup::read_cgroup(pid)
+                    .map(|cgroup| cgroup.label())
+                    .unwrap_or_default()
+            })
+            .clone();
+    }
+}
+
+/// Compares addresses numerically with IPv4 before IPv6 (or the other way around). IPv4-mapped
+/// IPv6 addresses count as IPv4.
+fn address_compare(a: Option<IpAddr>, b: Option<IpAddr>, ipv6_first: bool) -> Ordering {
+    let key = |addr: Option<IpAddr>| {
+        addr.map(|addr| {
+            let addr = addr.to_canonical();
+            (addr.is_ipv6() != ipv6_first, addr)
+        })
+    };
+    key(a).cmp(&key(b))
+}
+
 /// Compare strings, but always push empty strings to the end
 fn string_compare_with_empty(a: &str, b: &str, sort_order: SortOrder) -> Ordering {
     match sort_order {
@@ -1033,3 +3746,84 @@ fn remote_port_compare(a: u16, b: u16, sort_order: SortOrder) -> Ordering {
         },
     }
 }
+
+/// Names of environment variables whose values should not be shown by default: names with a
+/// part like `TOKEN` or `KEY` between `_`, `-` or `.`, e.g. `GITHUB_TOKEN`, but not `MONKEY`
+fn is_secret_name(name: &str) -> bool {
+    const SECRET_PARTS: [&str; 8] = [
+        "KEY",
+        "APIKEY",
+        "PASS",
+        "PASSWD",
+        "PASSWORD",
+        "TOKEN",
+        "SECRET",
+        "CREDENTIALS",
+    ];
+    let name = name.to_uppercase();
+    name.split(['_', '-', '.'])
+        .any(|part| SECRET_PARTS.contains(&part))
+}
+
+/// Shown instead of the value of a secret environment variable
+const SECRET_MASK: &str = "********";
+
+/// Whether an environment variable matches the lowercase `search`. The values of masked
+/// variables are not searched, so the results can't reveal them.
+fn environment_matches(name: &str, value: &str, search: &str, mask_secrets: bool) -> bool {
+    name.to_lowercase().contains(search)
+        || (!(mask_secrets && is_secret_name(name)) && value.to_lowercase().contains(search))
+}
+
+/// Variables like `PATH` or `LD_LIBRARY_PATH` holding a `:` separated list of directories
+fn is_path_list(name: &str, value: &str) -> bool {
+    value.contains(':')
+        && (name.ends_with("PATH") || value.split(':').all(|part| part.starts_with('/')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{environment_matches, is_secret_name};
+
+    #[test]
+    fn secret_names_have_a_secret_part() {
+        for name in [
+            "GITHUB_TOKEN",
+            "AWS_SECRET_ACCESS_KEY",
+            "api-key",
+            "OPENAI_APIKEY",
+            "DB_PASS",
+            "smtp.password",
+            "MYSQL_PASSWD",
+            "GOOGLE_APPLICATION_CREDENTIALS",
+        ] {
+            assert!(is_secret_name(name), "{name} is not masked");
+        }
+    }
+
+    #[test]
+    fn words_ending_like_secrets_are_not_secret() {
+        for name in [
+            "MONKEY",
+            "HOTKEY",
+            "PASSPORT_DIR",
+            "TOKENIZERS_PARALLELISM",
+            "KEYBOARD",
+        ] {
+            assert!(!is_secret_name(name), "{name} is masked");
+        }
+    }
+
+    #[test]
+    fn search_skips_masked_values() {
+        assert!(!environment_matches("GITHUB_TOKEN", "ghp_abc", "ghp", true));
+        assert!(environment_matches(
+            "GITHUB_TOKEN",
+            "ghp_abc",
+            "token",
+            true
+        ));
+        assert!(environment_matches("GITHUB_TOKEN", "ghp_abc", "ghp", false));
+        assert!(environment_matches("HOME", "/root", "root", true));
+    }
+}
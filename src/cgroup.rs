@@ -0,0 +1,275 @@
+use std::fs;
+
+/// A container a process runs in, derived from its cgroup path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Container {
+    /// Container runtime, e.g. "docker", "containerd" or "podman"; "container" if the path
+    /// doesn't tell
+    pub runtime: &'static str,
+    /// Full container id
+    pub id: String,
+}
+
+impl Container {
+    /// Short name like "docker:3f2a1b9c0d1e"
+    pub fn short_name(&self) -> String {
+        format!("{}:{}", self.runtime, &self.id[..self.id.len().min(12)])
+    }
+}
+
+/// Where a process lives according to its cgroup.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CgroupInfo {
+    /// cgroup path, e.g. `/system.slice/nginx.service`
+    pub path: String,
+    /// Innermost systemd unit, e.g. `nginx.service`
+    pub systemd_unit: Option<String>,
+    pub container: Option<Container>,
+    /// UID of the Kubernetes pod
+    pub pod_uid: Option<String>,
+}
+
+impl CgroupInfo {
+    /// Short description for the connection table: the container, else the pod, else the unit
+    pub fn label(&self) -> String {
+        if let Some(container) = &self.container {
+            container.short_name()
+        } else if let Some(pod_uid) = &self.pod_uid {
+            format!("pod:{pod_uid}")
+        } else {
+            self.systemd_unit.clone().unwrap_or_default()
+        }
+    }
+}
+
+/// Read the cgroup of process `pid` from `/proc/<pid>/cgroup`. The unified (v2) hierarchy is
+/// preferred, otherwise the systemd hierarchy of cgroup v1 is used.
+pub fn read_cgroup(pid: u32) -> Option<CgroupInfo> {
+    let content = fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    let paths = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            let id = fields.next()?;
+            let controllers = fields.next()?;
+            Some((id, controllers, fields.next()?))
+        })
+        .collect::<Vec<_>>();
+    let path = paths
+        .iter()
+        .find(|(id, controllers, _)| *id == "0" && controllers.is_empty())
+        .or_else(|| {
+            paths
+                .iter()
+                .find(|(_, controllers, _)| *controllers == "name=systemd")
+        })
+        .or(paths.first())
+        .map(|(_, _, path)| *path)?;
+    Some(CgroupInfo {
+        path: path.to_string(),
+        systemd_unit: systemd_unit_from_path(path),
+        container: paths
+            .iter()
+            .find_map(|(_, _, path)| container_from_path(path)),
+        pod_uid: paths
+            .iter()
+            .find_map(|(_, _, path)| pod_uid_from_path(path)),
+    })
+}
+
+/// Read the container of process `pid` from `/proc/<pid>/cgroup`.
+pub fn read_container(pid: u32) -> Option<Container> {
+    read_cgroup(pid)?.container
+}
+
+/// The innermost systemd unit of a cgroup path; slices only group units and are skipped.
+fn systemd_unit_from_path(path: &str) -> Option<String> {
+    const UNIT_SUFFIXES: [&str; 5] = [".service", ".scope", ".socket", ".mount", ".swap"];
+    path.rsplit('/')
+        .find(|segment| UNIT_SUFFIXES.iter().any(|suffix| segment.ends_with(suffix)))
+        .map(str::to_string)
+}
+
+/// Find a Kubernetes pod UID in a cgroup path, e.g. `/kubepods/burstable/pod<uid>/...` or
+/// `/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod<uid>.slice/...`.
+fn pod_uid_from_path(path: &str) -> Option<String> {
+    path.split('/')
+        .filter(|segment| segment.starts_with("kubepods") || segment.starts_with("pod"))
+        .find_map(|segment| {
+            let segment = segment.strip_suffix(".slice").unwrap_or(segment);
+            let (_, uid) = segment.rsplit_once("pod")?;
+            (uid.len() >= 32).then(|| uid.replace('_', "-"))
+        })
+}
+
+/// Find a container id in a cgroup path. Handles the cgroupfs layout (`/docker/<id>`) and the
+/// systemd layout (`/system.slice/docker-<id>.scope`) of the common runtimes. A bare id only
+/// names its runtime through the parent segments, e.g. not under `/kubepods/...`.
+fn container_from_path(path: &str) -> Option<Container> {
+    const PREFIXES: [(&str, &str); 5] = [
+        ("docker-", "docker"),
+        ("cri-containerd-", "containerd"),
+        ("crio-", "cri-o"),
+        ("libpod-", "podman"),
+        ("containerd-", "containerd"),
+    ];
+    let mut parents = vec![];
+    for segment in path.split('/') {
+        let name = segment.strip_suffix(".scope").unwrap_or(segment);
+        for (prefix, runtime) in PREFIXES {
+            if let Some(id) = name.strip_prefix(prefix)
+                && is_container_id(id)
+            {
+                return Some(Container {
+                    runtime,
+                    id: id.to_string(),
+                });
+            }
+        }
+        if is_container_id(name) {
+            let runtime = if parents.contains(&"docker") {
+                "docker"
+            } else if parents.contains(&"libpod_parent") {
+                "podman"
+            } else {
+                "container"
+            };
+            return Some(Container {
+                runtime,
+                id: name.to_string(),
+            });
+        }
+        parents.push(segment);
+    }
+    None
+}
+
+/// Container ids are 64 hex digits.
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f2a1b9c0d1e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f90";
+
+    fn container(path: &str) -> Option<(&'static str, String)> {
+        container_from_path(path).map(|container| (container.runtime, container.id))
+    }
+
+    #[test]
+    fn finds_containers_of_the_cgroupfs_layout() {
+        assert_eq!(
+            container(&format!("/docker/{ID}")),
+            Some(("docker", ID.into()))
+        );
+        assert_eq!(
+            container(&format!("/libpod_parent/libpod-{ID}")),
+            Some(("podman", ID.into()))
+        );
+        assert_eq!(
+            container(&format!(
+                "/kubepods/burstable/pod0b8c5a1e-7f2d-4c3b-9a6e-1d2f3a4b5c6d/{ID}"
+            )),
+            Some(("container", ID.into()))
+        );
+    }
+
+    #[test]
+    fn finds_containers_of_the_systemd_layout() {
+        for (path, runtime) in [
+            (format!("/system.slice/docker-{ID}.scope"), "docker"),
+            (
+                format!("/machine.slice/libpod-{ID}.scope/container"),
+                "podman",
+            ),
+            (
+                format!(
+                    "/kubepods.slice/kubepods-besteffort.slice/\
+                     kubepods-besteffort-pod0b8c5a1e_7f2d_4c3b_9a6e_1d2f3a4b5c6d.slice/\
+                     cri-containerd-{ID}.scope"
+                ),
+                "containerd",
+            ),
+            (
+                format!(
+                    "/kubepods.slice/kubepods-burstable.slice/\
+                     kubepods-burstable-pod0b8c5a1e_7f2d_4c3b_9a6e_1d2f3a4b5c6d.slice/\
+                     crio-{ID}.scope"
+                ),
+                "cri-o",
+            ),
+        ] {
+            assert_eq!(container(&path), Some((runtime, ID.into())), "{path}");
+        }
+    }
+
+    #[test]
+    fn ignores_paths_without_a_container() {
+        assert_eq!(container("/system.slice/nginx.service"), None);
+        assert_eq!(
+            container("/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+        // Not 64 hex digits
+        assert_eq!(container(&format!("/docker/{}", &ID[1..])), None);
+        assert_eq!(container("/system.slice/docker-not-an-id.scope"), None);
+    }
+
+    #[test]
+    fn finds_pod_uids_of_both_layouts() {
+        let uid = Some("0b8c5a1e-7f2d-4c3b-9a6e-1d2f3a4b5c6d".to_string());
+        assert_eq!(
+            pod_uid_from_path(&format!(
+                "/kubepods/burstable/pod0b8c5a1e-7f2d-4c3b-9a6e-1d2f3a4b5c6d/{ID}"
+            )),
+            uid
+        );
+        assert_eq!(
+            pod_uid_from_path(&format!(
+                "/kubepods.slice/kubepods-burstable.slice/\
+                 kubepods-burstable-pod0b8c5a1e_7f2d_4c3b_9a6e_1d2f3a4b5c6d.slice/\
+                 cri-containerd-{ID}.scope"
+            )),
+            uid
+        );
+        assert_eq!(
+            pod_uid_from_path("/kubepods.slice/kubepods-burstable.slice"),
+            None
+        );
+        assert_eq!(pod_uid_from_path("/system.slice/podman.service"), None);
+    }
+
+    #[test]
+    fn finds_the_innermost_systemd_unit() {
+        assert_eq!(
+            systemd_unit_from_path("/system.slice/nginx.service"),
+            Some("nginx.service".to_string())
+        );
+        assert_eq!(
+            systemd_unit_from_path(
+                "/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1234.scope"
+            ),
+            Some("app-firefox-1234.scope".to_string())
+        );
+        assert_eq!(
+            systemd_unit_from_path(&format!("/system.slice/docker-{ID}.scope")),
+            Some(format!("docker-{ID}.scope"))
+        );
+        assert_eq!(systemd_unit_from_path("/user.slice/user-1000.slice"), None);
+        assert_eq!(systemd_unit_from_path(&format!("/docker/{ID}")), None);
+    }
+
+    #[test]
+    fn labels_prefer_the_container() {
+        let info = CgroupInfo {
+            path: format!("/system.slice/docker-{ID}.scope"),
+            systemd_unit: Some(format!("docker-{ID}.scope")),
+            container: container_from_path(&format!("/system.slice/docker-{ID}.scope")),
+            pod_uid: None,
+        };
+        assert_eq!(info.label(), "docker:3f2a1b9c0d1e");
+    }
+}
@@ -0,0 +1,21 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Live view of network connections in a terminal.
+#[derive(Parser, Debug, Default)]
+#[command(version, about)]
+pub struct Cli {
+    /// Show sockets of all network namespaces (containers, `ip netns`), not only netview's own.
+    /// Linux only; reading other namespaces usually requires root.
+    #[arg(short = 'n', long)]
+    pub all_namespaces: bool,
+    /// Don't capture the mouse, e.g. to select text with the terminal's own selection.
+    #[arg(long)]
+    pub no_mouse: bool,
+    /// Append every refresh of the connection list to FILE, to be shown later with `--replay`.
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Show the connections recorded in FILE instead of the live ones.
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+}
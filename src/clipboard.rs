@@ -0,0 +1,69 @@
+//! Copying text to the clipboard with OSC 52 escape sequences. The terminal sets its own
+//! clipboard, so this also works over SSH and without a clipboard daemon.
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use std::io::{self, Write};
+
+/// Asks the terminal to put `text` into the system clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
+
+/// Replaces `{name}` placeholders in `template` by `value(name)`. Unknown placeholders and
+/// unmatched braces are kept as they are.
+pub fn expand_template(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let placeholder = rest
+            .find('}')
+            .and_then(|end| Some((end, value(&rest[1..end])?)));
+        match placeholder {
+            Some((end, value)) => {
+                text.push_str(&value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_template;
+
+    fn expand(template: &str) -> String {
+        expand_template(template, |name| match name {
+            "remote" => Some("10.0.0.2:443".to_string()),
+            "pid" => Some("42".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn replaces_known_placeholders() {
+        assert_eq!(expand("{remote}"), "10.0.0.2:443");
+        assert_eq!(expand("pid {pid} to {remote}."), "pid 42 to 10.0.0.2:443.");
+        assert_eq!(expand("{pid}{pid}"), "4242");
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_unmatched_braces() {
+        assert_eq!(expand("{unknown} {pid}"), "{unknown} 42");
+        assert_eq!(expand("pid {"), "pid {");
+        assert_eq!(expand("{pid"), "{pid");
+        assert_eq!(expand("}{pid}"), "}42");
+        assert_eq!(expand("{{pid}}"), "{42}");
+        assert_eq!(expand("{a {pid}"), "{a 42");
+        assert_eq!(expand(""), "");
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::PathBuf,
+};
+use toml_edit::DocumentMut;
+
+use crate::app::ColumnLayout;
+use crate::keys::KeyConfig;
+use crate::theme::{BUILT_IN_THEMES, Theme};
+
+/// Text copied for a connection unless the config file has a `copy_template`
+pub const DEFAULT_COPY_TEMPLATE: &str = "{proto} {local} {remote} {state} {pid} {process}";
+
+/// Settings persisted in `netview/config.toml` below the user's config directory.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    /// Name of the active built-in or user theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Show each connection on two lines to fit narrow terminals
+    pub compact_rows: bool,
+    /// Sort IPv6 addresses before IPv4 addresses
+    pub ipv6_first: bool,
+    /// Connection table columns in display order
+    pub columns: Vec<ColumnLayout>,
+    /// User defined themes by name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, Theme>,
+    /// Text copied for a connection with `y`, with placeholders like `{remote}` or `{pid}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_template: Option<String>,
+    /// Key binding changes by context
+    #[serde(skip_serializing_if = "KeyConfig::is_empty")]
+    pub keys: KeyConfig,
+}
+
+impl Config {
+    /// Location of the config file, e.g. `~/.config/netview/config.toml` on Linux.
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("netview").join("config.toml"))
+    }
+
+    /// Loads the config file. A missing or unreadable file yields the defaults, a file with
+    /// errors yields the error.
+    pub fn load() -> color_eyre::Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        toml::from_str(&content)
+            .map_err(|error| color_eyre::eyre::eyre!("{}: {}", path.display(), error.message()))
+    }
+
+    /// The configured copy template, or the default one
+    pub fn copy_template(&self) -> &str {
+        self.copy_template
+            .as_deref()
+            .unwrap_or(DEFAULT_COPY_TEMPLATE)
+    }
+
+    /// Names of the built-in themes followed by the user themes
+    pub fn theme_names(&self) -> Vec<String> {
+        let mut names = BUILT_IN_THEMES.map(str::to_string).to_vec();
+        for name in self.themes.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+
+    /// The configured theme; user themes take precedence over built-in themes of the same name.
+    /// Unknown names fall back to the dark theme.
+    pub fn active_theme(&self) -> Theme {
+        let name = self.theme.as_deref().unwrap_or(BUILT_IN_THEMES[0]);
+        self.themes
+            .get(name)
+            .cloned()
+            .or_else(|| Theme::built_in(name))
+            .unwrap_or_else(Theme::dark)
+    }
+
+    /// Writes the changed settings to the config file, creating its directory if needed.
+    pub fn save(&self) -> color_eyre::Result<()> {
+        let path = Self::path().ok_or_else(|| color_eyre::eyre::eyre!("No config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        fs::write(path, self.update(&content)?)?;
+        Ok(())
+    }
+
+    /// Replaces the top-level keys of the config file `content` whose values differ from this
+    /// config, so comments and formatting of the other keys are kept.
+    fn update(&self, content: &str) -> color_eyre::Result<String> {
+        let mut document: DocumentMut = content.parse()?;
+        // Compare the settings as serialized, so differently written but equal values are kept
+        let saved = toml::Table::try_from(toml::from_str::<Config>(content)?)?;
+        let current = toml::Table::try_from(self)?;
+        let changed: DocumentMut = toml::to_string_pretty(self)?.parse()?;
+        let keys: BTreeSet<&String> = saved.keys().chain(current.keys()).collect();
+        for key in keys {
+            if saved.get(key) == current.get(key) {
+                continue;
+            }
+            match changed.get(key) {
+                Some(item) => document[key.as_str()] = item.clone(),
+                None => {
+                    document.remove(key);
+                }
+            }
+        }
+        Ok(document.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn saving_keeps_comments_and_unchanged_keys() {
+        let content = "# my settings\ncompact_rows = false # for wide terminals\n\n# keep q\n[keys.global]\n\"ctrl-n\" = \"down\"\n";
+        let mut config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.update(content).unwrap(), content);
+
+        config.ipv6_first = true;
+        config.theme = Some("light".to_string());
+        let updated = config.update(content).unwrap();
+        assert!(updated.starts_with("# my settings\ncompact_rows = false # for wide terminals\n"));
+        assert!(updated.contains("ipv6_first = true"));
+        assert!(updated.contains("theme = \"light\""));
+        assert!(updated.contains("# keep q\n[keys.global]\n\"ctrl-n\" = \"down\"\n"));
+        assert_eq!(
+            toml::from_str::<Config>(&updated).unwrap().theme,
+            config.theme
+        );
+    }
+}
@@ -36,6 +36,8 @@ pub enum Event {
 pub enum AppEvent {
     /// Set sort column
     Sort(SortColumn),
+    /// Add a secondary sort column
+    ThenSort(SortColumn),
     /// Move line selection up.
     ScrollUpSelection,
     /// Move line selection down.
@@ -44,16 +46,98 @@ pub enum AppEvent {
     ScrollUpPage,
     /// Scroll the table down one page
     ScrollDownPage,
+    /// Scroll the table up half a page
+    ScrollUpHalfPage,
+    /// Scroll the table down half a page
+    ScrollDownHalfPage,
+    /// Scroll up by a number of lines, e.g. by the mouse wheel
+    ScrollUpRows(usize),
+    /// Scroll down by a number of lines
+    ScrollDownRows(usize),
+    /// Select the connection at an index of the table, e.g. by a click
+    SelectConnection(usize),
+    /// Select the first line
+    ScrollToTop,
+    /// Select the last line
+    ScrollToBottom,
     /// Show the help page
     ShowHelp,
     /// Show info about the process of a connection
     ShowProcessInfo,
+    /// Switch to the next tab of the process info view
+    NextTab,
+    /// Switch to the previous tab of the process info view
+    PreviousTab,
     /// Toggle display of ipv4/ipv6 connections
     ToggleIpVersion,
-    /// Toggle display of tcp/udp connections
+    /// Open or close the protocol selection
     ToggleProtoVersion,
+    /// Open or close the user selection
+    ToggleUserFilter,
+    /// Open or close the network namespace selection
+    ToggleNamespaceFilter,
+    /// Open or close the unit/container selection
+    ToggleCgroupFilter,
+    /// Open or close the column chooser
+    ToggleColumnChooser,
+    /// Switch between one and two lines per connection
+    ToggleCompactRows,
+    /// Switch to the next color theme
+    NextTheme,
+    /// Move the highlighted column of the column chooser to the left
+    MoveColumnUp,
+    /// Move the highlighted column of the column chooser to the right
+    MoveColumnDown,
+    /// Widen (positive) or narrow (negative) the highlighted column
+    ResizeColumn(i16),
+    /// Fit the width of the highlighted column to its content
+    ResetColumnWidth,
+    /// Toggle or choose the highlighted item of a selection overlay
+    SelectItem,
+    /// Start typing a search
+    StartSearch,
+    /// A character typed into the search
+    SearchInput(char),
+    /// Remove the last character of the search
+    SearchBackspace,
+    /// Stop typing and keep the search
+    FinishSearch,
+    /// Stop typing and clear the search
+    CancelSearch,
+    /// Select the next connection matching the search
+    NextMatch,
+    /// Select the previous connection matching the search
+    PreviousMatch,
+    /// Show or hide values of secret environment variables
+    ToggleMaskSecrets,
+    /// Sort environment variables by name or value
+    ToggleEnvironmentSort,
     /// Toggle display of addresses as IP or DNS names
     ToggleDnsResolution,
+    /// Sort addresses by resolved name or numerically
+    ToggleAddressSort,
+    /// Keep or release the current row order
+    ToggleFreezeOrder,
+    /// Mark or unmark the selected connection
+    ToggleMark,
+    /// Start a range of rows to mark, or mark the started range
+    ToggleVisualMark,
+    /// Remove all marks
+    ClearMarks,
+    /// Open or close the bulk action menu
+    ToggleBulkActions,
+    /// Copy the selected connection to the clipboard
+    CopySelection,
+    /// Open or close the menu of fields to copy
+    ToggleCopyMenu,
+    /// Open or close the export format selection
+    ToggleExportMenu,
+    /// Show the next or previous snapshot of a replay
+    StepReplay(bool),
+    /// Play a replay faster or slower
+    ChangeReplaySpeed(bool),
+    /// Send the signal waiting for confirmation
+    ConfirmSignal,
     /// Quit the application.
     Quit,
     /// Pause table updates
@@ -78,8 +162,13 @@ impl Default for EventHandler {
 impl EventHandler {
     /// Constructs a new instance of [`EventHandler`] and spawns a new thread to handle events.
     pub fn new() -> Self {
+        Self::with_tick_rate(Duration::from_secs_f64(1.0 / TICK_FPS))
+    }
+
+    /// Like [`EventHandler::new`], with tick events every `tick_rate` instead of the default
+    pub fn with_tick_rate(tick_rate: Duration) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
-        let actor = EventTask::new(sender.clone());
+        let actor = EventTask::new(sender.clone(), tick_rate);
         tokio::spawn(async { actor.run().await });
         Self { sender, receiver }
     }
@@ -115,21 +204,22 @@ impl EventHandler {
 struct EventTask {
     /// Event sender channel.
     sender: mpsc::UnboundedSender<Event>,
+    /// Time between tick events
+    tick_rate: Duration,
 }
 
 impl EventTask {
     /// Constructs a new instance of [`EventThread`].
-    fn new(sender: mpsc::UnboundedSender<Event>) -> Self {
-        Self { sender }
+    fn new(sender: mpsc::UnboundedSender<Event>, tick_rate: Duration) -> Self {
+        Self { sender, tick_rate }
     }
 
     /// Runs the event thread.
     ///
     /// This function emits tick events at a fixed rate and polls for crossterm events in between.
     async fn run(self) -> color_eyre::Result<()> {
-        let tick_rate = Duration::from_secs_f64(1.0 / TICK_FPS);
         let mut reader = crossterm::event::EventStream::new();
-        let mut tick = tokio::time::interval(tick_rate);
+        let mut tick = tokio::time::interval(self.tick_rate);
         loop {
             let tick_delay = tick.tick();
             let crossterm_event = reader.next().fuse();
@@ -0,0 +1,309 @@
+//! Writing the shown connections to JSON, CSV or Markdown files.
+
+use serde::Serialize;
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Write},
+    net::IpAddr,
+    path::PathBuf,
+};
+
+use crate::app::ConnectionEntry;
+
+/// File formats of the export dialog
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::Json,
+        ExportFormat::Csv,
+        ExportFormat::Markdown,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Markdown => "Markdown table",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// All fields of a connection, including those without a table column
+#[derive(Serialize)]
+struct ExportedConnection<'a> {
+    proto: &'a str,
+    local_ip: &'a str,
+    local_addr: IpAddr,
+    local_port: u16,
+    remote_ip: &'a str,
+    remote_addr: Option<IpAddr>,
+    remote_port: u16,
+    state: &'a str,
+    pids: &'a [u32],
+    process: &'a str,
+    user: &'a str,
+    namespace: &'a str,
+    cgroup: &'a str,
+    inode: u64,
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rate: &'a str,
+    /// Time from when netview first saw the connection to the last refresh
+    age_seconds: u64,
+    closed: bool,
+}
+
+impl<'a> ExportedConnection<'a> {
+    const FIELDS: [&'static str; 19] = [
+        "proto",
+        "local_ip",
+        "local_addr",
+        "local_port",
+        "remote_ip",
+        "remote_addr",
+        "remote_port",
+        "state",
+        "pids",
+        "process",
+        "user",
+        "namespace",
+        "cgroup",
+        "inode",
+        "rx_bytes",
+        "tx_bytes",
+        "rate",
+        "age_seconds",
+        "closed",
+    ];
+
+    fn new(entry: &'a ConnectionEntry) -> Self {
+        Self {
+            proto: &entry.proto,
+            local_ip: &entry.local_ip,
+            local_addr: entry.local_addr,
+            local_port: entry.local_port,
+            remote_ip: &entry.remote_ip,
+            remote_addr: entry.remote_addr,
+            remote_port: entry.remote_port,
+            state: &entry.state,
+            pids: &entry.pids,
+            process: &entry.process,
+            user: &entry.user,
+            namespace: &entry.namespace,
+            cgroup: &entry.cgroup,
+            inode: entry.inode,
+            rx_bytes: entry.rx_bytes,
+            tx_bytes: entry.tx_bytes,
+            rate: &entry.data_rate,
+            age_seconds: entry
+                .last_update
+                .saturating_duration_since(entry.creation_time)
+                .as_secs(),
+            closed: entry.closed,
+        }
+    }
+
+    /// Field values in the order of `FIELDS`; PIDs are separated by spaces
+    fn values(&self) -> [String; 19] {
+        [
+            self.proto.to_string(),
+            self.local_ip.to_string(),
+            self.local_addr.to_string(),
+            self.local_port.to_string(),
+            self.remote_ip.to_string(),
+            self.remote_addr
+                .map_or(String::new(), |addr| addr.to_string()),
+            self.remote_port.to_string(),
+            self.state.to_string(),
+            self.pids
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+            self.process.to_string(),
+            self.user.to_string(),
+            self.namespace.to_string(),
+            self.cgroup.to_string(),
+            self.inode.to_string(),
+            self.rx_bytes.to_string(),
+            self.tx_bytes.to_string(),
+            self.rate.to_string(),
+            self.age_seconds.to_string(),
+            self.closed.to_string(),
+        ]
+    }
+}
+
+/// The connections as the content of a file in `format`
+pub fn render(entries: &[ConnectionEntry], format: ExportFormat) -> color_eyre::Result<String> {
+    let connections: Vec<ExportedConnection> =
+        entries.iter().map(ExportedConnection::new).collect();
+    let mut text = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&connections)?,
+        ExportFormat::Csv => {
+            let mut lines = vec![ExportedConnection::FIELDS.join(",")];
+            lines.extend(connections.iter().map(|connection| {
+                connection
+                    .values()
+                    .iter()
+                    .map(|value| csv_field(value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }));
+            lines.join("\n")
+        }
+        ExportFormat::Markdown => {
+            let row = |values: &mut dyn Iterator<Item = String>| {
+                format!("| {} |", values.collect::<Vec<_>>().join(" | "))
+            };
+            let mut lines = vec![
+                row(&mut ExportedConnection::FIELDS
+                    .iter()
+                    .map(|field| field.to_string())),
+                row(&mut ExportedConnection::FIELDS.iter().map(|_| "---".to_string())),
+            ];
+            lines.extend(connections.iter().map(|connection| {
+                row(&mut connection.values().iter().map(|value| markdown_cell(value)))
+            }));
+            lines.join("\n")
+        }
+    };
+    text.push('\n');
+    Ok(text)
+}
+
+/// Writes the connections to a timestamped file in the current directory and returns its path
+pub fn write(entries: &[ConnectionEntry], format: ExportFormat) -> color_eyre::Result<PathBuf> {
+    let content = render(entries, format)?;
+    let (mut file, path) = create_file("netview", format.extension())?;
+    file.write_all(content.as_bytes())?;
+    Ok(path)
+}
+
+/// Creates a new file `<prefix>-<timestamp>.<extension>` in the current directory. Existing
+/// files are never overwritten; a counter is appended to the name instead, e.g. `-2`.
+pub fn create_file(prefix: &str, extension: &str) -> color_eyre::Result<(File, PathBuf)> {
+    let stem = format!("{prefix}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let dir = std::env::current_dir()?;
+    for counter in 1.. {
+        let file_name = if counter == 1 {
+            format!("{stem}.{extension}")
+        } else {
+            format!("{stem}-{counter}.{extension}")
+        };
+        let path = dir.join(file_name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    unreachable!("one of the file names is free")
+}
+
+/// Quotes a CSV value if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes the column separator of a Markdown table cell; line breaks would end the row
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn entry(process: &str) -> ConnectionEntry {
+        let now = Instant::now();
+        ConnectionEntry {
+            proto: "TCPv4".to_string(),
+            local_ip: "127.0.0.1".to_string(),
+            local_port: 8080,
+            remote_ip: "10.0.0.2".to_string(),
+            remote_port: 51000,
+            local_addr: "127.0.0.1".parse().unwrap(),
+            remote_addr: Some("10.0.0.2".parse().unwrap()),
+            state: "Established".to_string(),
+            pid: 42,
+            pids: vec![42, 43],
+            process: process.to_string(),
+            user: "www".to_string(),
+            namespace: String::new(),
+            cgroup: "web.service".to_string(),
+            inode: 1234,
+            creation_time: now,
+            rx_bytes: 10,
+            tx_bytes: 20,
+            data_rate: "0 B/s".to_string(),
+            last_update: now + Duration::from_secs(3),
+            closed: false,
+        }
+    }
+
+    const NAME: &str = "a,b \"c\" | d\ne";
+
+    #[test]
+    fn csv_quotes_separators_quotes_and_line_breaks() {
+        let csv = render(&[entry(NAME)], ExportFormat::Csv).unwrap();
+        assert_eq!(
+            csv,
+            format!(
+                "{}\n\
+                 TCPv4,127.0.0.1,127.0.0.1,8080,10.0.0.2,10.0.0.2,51000,Established,42 43,\
+                 \"a,b \"\"c\"\" | d\ne\",www,,web.service,1234,10,20,0 B/s,3,false\n",
+                ExportedConnection::FIELDS.join(",")
+            )
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_and_line_breaks() {
+        let markdown = render(&[entry(NAME)], ExportFormat::Markdown).unwrap();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[2],
+            "| TCPv4 | 127.0.0.1 | 127.0.0.1 | 8080 | 10.0.0.2 | 10.0.0.2 | 51000 | Established \
+             | 42 43 | a,b \"c\" \\| d<br>e | www |  | web.service | 1234 | 10 | 20 | 0 B/s | 3 \
+             | false |"
+        );
+    }
+
+    #[test]
+    fn json_has_all_fields() {
+        let json = render(&[entry(NAME)], ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let connection = value[0].as_object().unwrap();
+        let mut keys: Vec<&str> = connection.keys().map(String::as_str).collect();
+        let mut fields = ExportedConnection::FIELDS.to_vec();
+        keys.sort_unstable();
+        fields.sort_unstable();
+        assert_eq!(keys, fields);
+        assert_eq!(connection["process"], NAME);
+        assert_eq!(connection["age_seconds"], 3);
+    }
+}
@@ -0,0 +1,777 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::app::SortColumn;
+use crate::event::AppEvent;
+
+/// A key with modifiers, written like `q`, `Ctrl-d`, `Shift-Up` or `PageDown` in the config.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Terminals report Shift with upper case letters and Shift-Tab, the key says it already
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab => Self {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::BackTab,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => Self { code, modifiers },
+        }
+    }
+}
+
+impl From<&KeyEvent> for KeyChord {
+    fn from(key_event: &KeyEvent) -> Self {
+        Self::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = chord;
+        // A trailing `-` is the minus key, e.g. `-` or `Ctrl--`
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty())
+        {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier in key '{chord}'")),
+            };
+            key = rest;
+        }
+        let code = match key.to_lowercase().as_str() {
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            // Besides the names, the keys as shown in the help overlay are accepted
+            "up" | "↑" => KeyCode::Up,
+            "down" | "↓" => KeyCode::Down,
+            "left" | "←" => KeyCode::Left,
+            "right" | "→" => KeyCode::Right,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "insert" => KeyCode::Insert,
+            "delete" => KeyCode::Delete,
+            "pause" => KeyCode::Pause,
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                        Some(n) => KeyCode::F(n),
+                        None => return Err(format!("unknown key '{chord}'")),
+                    },
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl-"),
+            (KeyModifiers::ALT, "Alt-"),
+            (KeyModifiers::SHIFT, "Shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => f.write_str("Shift-Tab"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            // Named like in the config, crossterm names some keys differently on macOS
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            code => write!(f, "{code}"),
+        }
+    }
+}
+
+/// Where a key binding applies. Bindings of the active context take precedence over global ones.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum KeyContext {
+    Global,
+    /// Protocol, user, namespace, unit and column selection lists
+    Overlay,
+    /// The column chooser, in addition to the selection list keys
+    Columns,
+    /// The environment tab of the process info view
+    Environment,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 4] = [
+        KeyContext::Global,
+        KeyContext::Overlay,
+        KeyContext::Columns,
+        KeyContext::Environment,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Global => "Keys",
+            KeyContext::Overlay => "Selection lists",
+            KeyContext::Columns => "Column chooser",
+            KeyContext::Environment => "Environment tab",
+        }
+    }
+}
+
+/// Something a key can be bound to
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Action {
+    Quit,
+    Pause,
+    ShowProcessInfo,
+    NextTab,
+    PreviousTab,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    Top,
+    Bottom,
+    Find,
+    NextMatch,
+    PreviousMatch,
+    ToggleIpVersion,
+    SelectProtocols,
+    ToggleDns,
+    ToggleAddressSort,
+    FreezeOrder,
+    Mark,
+    VisualMark,
+    ClearMarks,
+    BulkActions,
+    Copy,
+    CopyField,
+    Export,
+    StepForward,
+    StepBack,
+    Faster,
+    Slower,
+    SelectUser,
+    SelectNamespace,
+    SelectCgroup,
+    ChooseColumns,
+    CompactRows,
+    NextTheme,
+    Help,
+    Sort(SortColumn),
+    ThenSort(SortColumn),
+    Select,
+    MoveColumnUp,
+    MoveColumnDown,
+    NarrowColumn,
+    WidenColumn,
+    FitColumn,
+    Search,
+    ToggleSecrets,
+    SortEnvironment,
+}
+
+impl Action {
+    /// Bindable actions with their names used in the config file
+    pub fn all() -> Vec<(&'static str, Action)> {
+        let mut actions = vec![
+            ("quit", Action::Quit),
+            ("pause", Action::Pause),
+            ("show-process-info", Action::ShowProcessInfo),
+            ("next-tab", Action::NextTab),
+            ("previous-tab", Action::PreviousTab),
+            ("up", Action::Up),
+            ("down", Action::Down),
+            ("page-up", Action::PageUp),
+            ("page-down", Action::PageDown),
+            ("half-page-up", Action::HalfPageUp),
+            ("half-page-down", Action::HalfPageDown),
+            ("top", Action::Top),
+            ("bottom", Action::Bottom),
+            ("find", Action::Find),
+            ("next-match", Action::NextMatch),
+            ("previous-match", Action::PreviousMatch),
+            ("toggle-ip-version", Action::ToggleIpVersion),
+            ("select-protocols", Action::SelectProtocols),
+            ("toggle-dns", Action::ToggleDns),
+            ("toggle-address-sort", Action::ToggleAddressSort),
+            ("freeze-order", Action::FreezeOrder),
+            ("mark", Action::Mark),
+            ("visual-mark", Action::VisualMark),
+            ("clear-marks", Action::ClearMarks),
+            ("bulk-actions", Action::BulkActions),
+            ("copy", Action::Copy),
+            ("copy-field", Action::CopyField),
+            ("export", Action::Export),
+            ("step-forward", Action::StepForward),
+            ("step-back", Action::StepBack),
+            ("faster", Action::Faster),
+            ("slower", Action::Slower),
+            ("select-user", Action::SelectUser),
+            ("select-namespace", Action::SelectNamespace),
+            ("select-cgroup", Action::SelectCgroup),
+            ("choose-columns", Action::ChooseColumns),
+            ("compact-rows", Action::CompactRows),
+            ("next-theme", Action::NextTheme),
+            ("help", Action::Help),
+        ];
+        actions.extend(
+            SortColumn::ALL
+                .iter()
+                .zip(SORT_ACTION_NAMES)
+                .map(|(column, name)| (name, Action::Sort(*column))),
+        );
+        actions.extend(
+            SortColumn::ALL
+                .iter()
+                .zip(THEN_SORT_ACTION_NAMES)
+                .map(|(column, name)| (name, Action::ThenSort(*column))),
+        );
+        actions.extend([
+            ("select", Action::Select),
+            ("move-column-up", Action::MoveColumnUp),
+            ("move-column-down", Action::MoveColumnDown),
+            ("narrow-column", Action::NarrowColumn),
+            ("widen-column", Action::WidenColumn),
+            ("fit-column", Action::FitColumn),
+            ("search", Action::Search),
+            ("toggle-secrets", Action::ToggleSecrets),
+            ("sort-environment", Action::SortEnvironment),
+        ]);
+        actions
+    }
+
+    pub fn name(&self) -> &'static str {
+        Self::all()
+            .into_iter()
+            .find(|(_, action)| action == self)
+            .map_or("", |(name, _)| name)
+    }
+
+    /// Description shown in the help overlay
+    pub fn description(&self) -> String {
+        match self {
+            Action::Quit => "Quit / close".into(),
+            Action::Pause => "Pause / resume list updates".into(),
+            Action::ShowProcessInfo => "Show process info".into(),
+            Action::NextTab => "Next process info tab".into(),
+            Action::PreviousTab => "Previous process info tab".into(),
+            Action::Up => "Move selection up".into(),
+            Action::Down => "Move selection down".into(),
+            Action::PageUp => "Scroll up a page".into(),
+            Action::PageDown => "Scroll down a page".into(),
+            Action::HalfPageUp => "Scroll up half a page".into(),
+            Action::HalfPageDown => "Scroll down half a page".into(),
+            Action::Top => "Go to the first line".into(),
+            Action::Bottom => "Go to the last line".into(),
+            Action::Find => "Search IPs, names, ports, processes".into(),
+            Action::NextMatch => "Next match".into(),
+            Action::PreviousMatch => "Previous match".into(),
+            Action::ToggleIpVersion => "Toggle IPv4 / IPv6 filter".into(),
+            Action::SelectProtocols => "Select shown protocols".into(),
+            Action::ToggleDns => "Toggle DNS resolution".into(),
+            Action::ToggleAddressSort => "Sort addresses by name / IP".into(),
+            Action::FreezeOrder => "Freeze / release row order".into(),
+            Action::Mark => "Mark / unmark connection".into(),
+            Action::VisualMark => "Mark a range of connections".into(),
+            Action::ClearMarks => "Clear all marks".into(),
+            Action::BulkActions => "Copy, save or signal marked rows".into(),
+            Action::Copy => "Copy connection to the clipboard".into(),
+            Action::CopyField => "Copy address, PID or command line".into(),
+            Action::Export => "Export shown connections to a file".into(),
+            Action::StepForward => "Replay: next refresh".into(),
+            Action::StepBack => "Replay: previous refresh".into(),
+            Action::Faster => "Replay: play faster".into(),
+            Action::Slower => "Replay: play slower".into(),
+            Action::SelectUser => "Select user filter".into(),
+            Action::SelectNamespace => "Select namespace (with -n)".into(),
+            Action::SelectCgroup => "Select unit / container".into(),
+            Action::ChooseColumns => "Choose, move and resize columns".into(),
+            Action::CompactRows => "Two lines per connection".into(),
+            Action::NextTheme => "Switch color theme".into(),
+            Action::Help => "This help".into(),
+            Action::Sort(column) => format!("Sort by {}", column.label()),
+            Action::ThenSort(column) => format!("Then sort by {}", column.label()),
+            Action::Select => "Toggle / choose item".into(),
+            Action::MoveColumnUp => "Move column left".into(),
+            Action::MoveColumnDown => "Move column right".into(),
+            Action::NarrowColumn => "Narrow column".into(),
+            Action::WidenColumn => "Widen column".into(),
+            Action::FitColumn => "Fit column to content".into(),
+            Action::Search => "Search names and values".into(),
+            Action::ToggleSecrets => "Show / mask secrets".into(),
+            Action::SortEnvironment => "Sort by name / value".into(),
+        }
+    }
+
+    /// The event sent when the action's key is pressed
+    pub fn event(&self) -> AppEvent {
+        match self {
+            Action::Quit => AppEvent::Quit,
+            Action::Pause => AppEvent::Pause,
+            Action::ShowProcessInfo => AppEvent::ShowProcessInfo,
+            Action::NextTab => AppEvent::NextTab,
+            Action::PreviousTab => AppEvent::PreviousTab,
+            Action::Up => AppEvent::ScrollUpSelection,
+            Action::Down => AppEvent::ScrollDownSelection,
+            Action::PageUp => AppEvent::ScrollUpPage,
+            Action::PageDown => AppEvent::ScrollDownPage,
+            Action::HalfPageUp => AppEvent::ScrollUpHalfPage,
+            Action::HalfPageDown => AppEvent::ScrollDownHalfPage,
+            Action::Top => AppEvent::ScrollToTop,
+            Action::Bottom => AppEvent::ScrollToBottom,
+            Action::Find => AppEvent::StartSearch,
+            Action::NextMatch => AppEvent::NextMatch,
+            Action::PreviousMatch => AppEvent::PreviousMatch,
+            Action::ToggleIpVersion => AppEvent::ToggleIpVersion,
+            Action::SelectProtocols => AppEvent::ToggleProtoVersion,
+            Action::ToggleDns => AppEvent::ToggleDnsResolution,
+            Action::ToggleAddressSort => AppEvent::ToggleAddressSort,
+            Action::FreezeOrder => AppEvent::ToggleFreezeOrder,
+            Action::Mark => AppEvent::ToggleMark,
+            Action::VisualMark => AppEvent::ToggleVisualMark,
+            Action::ClearMarks => AppEvent::ClearMarks,
+            Action::BulkActions => AppEvent::ToggleBulkActions,
+            Action::Copy => AppEvent::CopySelection,
+            Action::CopyField => AppEvent::ToggleCopyMenu,
+            Action::Export => AppEvent::ToggleExportMenu,
+            Action::StepForward => AppEvent::StepReplay(true),
+            Action::StepBack => AppEvent::StepReplay(false),
+            Action::Faster => AppEvent::ChangeReplaySpeed(true),
+            Action::Slower => AppEvent::ChangeReplaySpeed(false),
+            Action::SelectUser => AppEvent::ToggleUserFilter,
+            Action::SelectNamespace => AppEvent::ToggleNamespaceFilter,
+            Action::SelectCgroup => AppEvent::ToggleCgroupFilter,
+            Action::ChooseColumns => AppEvent::ToggleColumnChooser,
+            Action::CompactRows => AppEvent::ToggleCompactRows,
+            Action::NextTheme => AppEvent::NextTheme,
+            Action::Help => AppEvent::ShowHelp,
+            Action::Sort(column) => AppEvent::Sort(*column),
+            Action::ThenSort(column) => AppEvent::ThenSort(*column),
+            Action::Select => AppEvent::SelectItem,
+            Action::MoveColumnUp => AppEvent::MoveColumnUp,
+            Action::MoveColumnDown => AppEvent::MoveColumnDown,
+            Action::NarrowColumn => AppEvent::ResizeColumn(-1),
+            Action::WidenColumn => AppEvent::ResizeColumn(1),
+            Action::FitColumn => AppEvent::ResetColumnWidth,
+            Action::Search => AppEvent::StartSearch,
+            Action::ToggleSecrets => AppEvent::ToggleMaskSecrets,
+            Action::SortEnvironment => AppEvent::ToggleEnvironmentSort,
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|(action_name, _)| *action_name == name)
+            .map(|(_, action)| action)
+            .ok_or_else(|| format!("unknown action '{name}'"))
+    }
+}
+
+/// Config names of the sort actions, in the order of `SortColumn::ALL`
+const SORT_ACTION_NAMES: [&str; 12] = [
+    "sort-proto",
+    "sort-local-ip",
+    "sort-local-port",
+    "sort-remote-ip",
+    "sort-remote-port",
+    "sort-state",
+    "sort-pid",
+    "sort-process",
+    "sort-rate",
+    "sort-user",
+    "sort-namespace",
+    "sort-cgroup",
+];
+
+/// Config names of the secondary sort actions, in the order of `SortColumn::ALL`
+const THEN_SORT_ACTION_NAMES: [&str; 12] = [
+    "then-sort-proto",
+    "then-sort-local-ip",
+    "then-sort-local-port",
+    "then-sort-remote-ip",
+    "then-sort-remote-port",
+    "then-sort-state",
+    "then-sort-pid",
+    "then-sort-process",
+    "then-sort-rate",
+    "then-sort-user",
+    "then-sort-namespace",
+    "then-sort-cgroup",
+];
+
+/// Key binding overrides in the config file, by context. Keys map to action names; the action
+/// `none` removes a default binding.
+///
+/// ```toml
+/// [keys.global]
+/// "Ctrl-n" = "down"
+/// "x" = "quit"
+/// "q" = "none"
+/// ```
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct KeyConfig {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub global: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub overlay: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub columns: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+}
+
+impl KeyConfig {
+    pub fn is_empty(&self) -> bool {
+        KeyContext::ALL
+            .iter()
+            .all(|context| self.context(*context).is_empty())
+    }
+
+    fn context(&self, context: KeyContext) -> &BTreeMap<String, String> {
+        match context {
+            KeyContext::Global => &self.global,
+            KeyContext::Overlay => &self.overlay,
+            KeyContext::Columns => &self.columns,
+            KeyContext::Environment => &self.environment,
+        }
+    }
+}
+
+/// The active key bindings, in the order they are listed in the help overlay
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    bindings: Vec<(KeyContext, KeyChord, Action)>,
+}
+
+impl Default for KeyBindings {
+    /// The default keys, including vim style navigation
+    fn default() -> Self {
+        use Action::*;
+        use KeyContext::*;
+        let mut defaults: Vec<(KeyContext, &str, Action)> = vec![
+            (Global, "q", Quit),
+            (Global, "Esc", Quit),
+            (Global, "Ctrl-c", Quit),
+            (Global, "Space", Pause),
+            (Global, "Pause", Pause),
+            (Global, "Enter", ShowProcessInfo),
+            (Global, "Tab", NextTab),
+            (Global, "BackTab", PreviousTab),
+            (Global, "Up", Up),
+            (Global, "k", Up),
+            (Global, "Down", Down),
+            (Global, "j", Down),
+            (Global, "PageUp", PageUp),
+            (Global, "PageDown", PageDown),
+            (Global, "Ctrl-u", HalfPageUp),
+            (Global, "Ctrl-d", HalfPageDown),
+            (Global, "Home", Top),
+            (Global, "g", Top),
+            (Global, "End", Bottom),
+            (Global, "G", Bottom),
+            (Global, "/", Find),
+            (Global, "n", NextMatch),
+            (Global, "N", PreviousMatch),
+            (Global, "i", ToggleIpVersion),
+            (Global, "p", SelectProtocols),
+            (Global, "d", ToggleDns),
+            (Global, "D", ToggleAddressSort),
+            (Global, "f", FreezeOrder),
+            (Global, "m", Mark),
+            (Global, "V", VisualMark),
+            (Global, "M", ClearMarks),
+            (Global, "x", BulkActions),
+            (Global, "y", Copy),
+            (Global, "Y", CopyField),
+            (Global, "e", Export),
+            (Global, ".", StepForward),
+            (Global, ",", StepBack),
+            (Global, "+", Faster),
+            (Global, "-", Slower),
+            (Global, "u", SelectUser),
+            (Global, "c", SelectNamespace),
+            (Global, "o", SelectCgroup),
+            (Global, "l", ChooseColumns),
+            (Global, "z", CompactRows),
+            (Global, "t", NextTheme),
+            (Global, "h", Help),
+            (Global, "?", Help),
+        ];
+        // Digits stand for the columns in their default order, not their position on screen,
+        // so a key keeps sorting the same column when columns are moved or hidden
+        let digits = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "0"];
+        defaults.extend(
+            digits
+                .iter()
+                .zip(SortColumn::ALL)
+                .map(|(digit, column)| (Global, *digit, Sort(column))),
+        );
+        // Shift and a digit gives these characters on US keyboards; Alt works everywhere
+        let shifted_digits = ["!", "@", "#", "$", "%", "^", "&", "*", "(", ")"];
+        let alt_digits = [
+            "Alt-1", "Alt-2", "Alt-3", "Alt-4", "Alt-5", "Alt-6", "Alt-7", "Alt-8", "Alt-9",
+            "Alt-0",
+        ];
+        for ((shifted, alt), column) in shifted_digits.iter().zip(alt_digits).zip(SortColumn::ALL) {
+            defaults.push((Global, shifted, ThenSort(column)));
+            defaults.push((Global, alt, ThenSort(column)));
+        }
+        // The columns beyond the tenth get a letter
+        defaults.extend([
+            (Global, "Alt-n", Sort(SortColumn::Namespace)),
+            (Global, "Alt-N", ThenSort(SortColumn::Namespace)),
+            (Global, "Alt-u", Sort(SortColumn::Cgroup)),
+            (Global, "Alt-U", ThenSort(SortColumn::Cgroup)),
+        ]);
+        defaults.extend([
+            (Overlay, "Space", Select),
+            (Overlay, "Enter", Select),
+            (Columns, "Shift-Up", MoveColumnUp),
+            (Columns, "K", MoveColumnUp),
+            (Columns, "Shift-Down", MoveColumnDown),
+            (Columns, "J", MoveColumnDown),
+            (Columns, "Left", NarrowColumn),
+            (Columns, "-", NarrowColumn),
+            (Columns, "Right", WidenColumn),
+            (Columns, "+", WidenColumn),
+            (Columns, "a", FitColumn),
+            (Environment, "/", Search),
+            (Environment, "m", ToggleSecrets),
+            (Environment, "s", SortEnvironment),
+        ]);
+        Self {
+            bindings: defaults
+                .into_iter()
+                .map(|(context, chord, action)| {
+                    (context, chord.parse().expect("valid default key"), action)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// The default bindings changed by the config. Entries with unknown keys or actions are
+    /// skipped, like other invalid settings.
+    pub fn with_config(config: &KeyConfig) -> Self {
+        let mut bindings = Self::default();
+        for context in KeyContext::ALL {
+            for (chord, action) in config.context(context) {
+                let Ok(chord) = chord.parse::<KeyChord>() else {
+                    continue;
+                };
+                let action = match action.as_str() {
+                    "none" => None,
+                    name => match name.parse::<Action>() {
+                        Ok(action) => Some(action),
+                        Err(_) => continue,
+                    },
+                };
+                bindings
+                    .bindings
+                    .retain(|(c, binding, _)| !(*c == context && *binding == chord));
+                if let Some(action) = action {
+                    bindings.bindings.push((context, chord, action));
+                }
+            }
+        }
+        bindings
+    }
+
+    /// The action bound to `key_event` in `context`. Upper case letters fall back to the
+    /// binding of the lower case letter, so that e.g. `Q` also quits unless bound otherwise.
+    pub fn lookup(&self, context: KeyContext, key_event: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from(key_event);
+        let find = |chord: KeyChord| {
+            self.bindings
+                .iter()
+                .find(|(c, binding, _)| *c == context && *binding == chord)
+                .map(|(_, _, action)| *action)
+        };
+        find(chord).or_else(|| match chord.code {
+            KeyCode::Char(c) if c.is_uppercase() => find(KeyChord::new(
+                KeyCode::Char(c.to_ascii_lowercase()),
+                chord.modifiers,
+            )),
+            _ => None,
+        })
+    }
+
+    /// Actions of `context` with their keys, in binding order
+    pub fn actions(&self, context: KeyContext) -> Vec<(Action, Vec<KeyChord>)> {
+        let mut actions: Vec<(Action, Vec<KeyChord>)> = Vec::new();
+        for (_, chord, action) in self.bindings.iter().filter(|(c, _, _)| *c == context) {
+            match actions.iter_mut().find(|(a, _)| a == action) {
+                Some((_, chords)) => chords.push(*chord),
+                None => actions.push((*action, vec![*chord])),
+            }
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord { code, modifiers }
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(
+            "Ctrl-d".parse(),
+            Ok(chord(KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!("Alt-Up".parse(), Ok(chord(KeyCode::Up, KeyModifiers::ALT)));
+        assert_eq!(
+            "G".parse(),
+            Ok(chord(KeyCode::Char('G'), KeyModifiers::NONE))
+        );
+        // Shift with a letter is the upper case letter, as terminals report it
+        assert_eq!(
+            "Shift-g".parse(),
+            Ok(chord(KeyCode::Char('G'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "PageDown".parse(),
+            Ok(chord(KeyCode::PageDown, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "Ctrl--".parse(),
+            Ok(chord(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_chords() {
+        for invalid in ["", "Hyper-a", "Ctrl-", "PageSideways", "Fx", "ab"] {
+            assert!(invalid.parse::<KeyChord>().is_err(), "{invalid:?} parsed");
+        }
+    }
+
+    #[test]
+    fn displayed_chords_parse_back() {
+        for text in [
+            "Ctrl-d",
+            "Alt-Up",
+            "G",
+            "PageDown",
+            "Shift-Down",
+            "Shift-Tab",
+            "Space",
+            "Enter",
+            "Delete",
+            "Backspace",
+            "F5",
+            "-",
+        ] {
+            let parsed: KeyChord = text.parse().unwrap();
+            assert_eq!(
+                parsed.to_string().parse::<KeyChord>(),
+                Ok(parsed),
+                "{text} shown as {parsed}"
+            );
+        }
+    }
+
+    #[test]
+    fn chords_of_key_events_match_parsed_chords() {
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from(&event), "G".parse().unwrap());
+        let event = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from(&event), "Shift-Tab".parse().unwrap());
+    }
+
+    #[test]
+    fn every_column_has_default_sort_keys() {
+        let actions: Vec<Action> = KeyBindings::default()
+            .actions(KeyContext::Global)
+            .into_iter()
+            .map(|(action, _)| action)
+            .collect();
+        for column in SortColumn::ALL {
+            assert!(actions.contains(&Action::Sort(column)), "{column:?}");
+            assert!(actions.contains(&Action::ThenSort(column)), "{column:?}");
+        }
+    }
+
+    #[test]
+    fn config_overrides_replace_default_bindings() {
+        let config: KeyConfig = toml::from_str(
+            r#"
+            [global]
+            "q" = "none"
+            "x" = "quit"
+            "Ctrl-n" = "down"
+            "Alt-Up" = "no-such-action"
+            "#,
+        )
+        .unwrap();
+        let bindings = KeyBindings::with_config(&config);
+        let lookup =
+            |code, modifiers| bindings.lookup(KeyContext::Global, &KeyEvent::new(code, modifiers));
+        assert_eq!(lookup(KeyCode::Char('q'), KeyModifiers::NONE), None);
+        assert_eq!(
+            lookup(KeyCode::Char('x'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            lookup(KeyCode::Char('n'), KeyModifiers::CONTROL),
+            Some(Action::Down)
+        );
+        // Other defaults and invalid entries leave the defaults alone
+        assert_eq!(lookup(KeyCode::Esc, KeyModifiers::NONE), Some(Action::Quit));
+        assert_eq!(lookup(KeyCode::Up, KeyModifiers::ALT), None);
+        let defaults = KeyBindings::default();
+        assert_eq!(
+            defaults.lookup(
+                KeyContext::Global,
+                &KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE)
+            ),
+            Some(Action::BulkActions)
+        );
+    }
+}
@@ -1,9 +1,22 @@
 use std::time::Duration;
 
-use crate::app::App;
+use clap::Parser;
+
+use crate::{app::App, cli::Cli, recording::Source};
 
 pub mod app;
+#[cfg(target_os = "linux")]
+pub mod cgroup;
+pub mod cli;
+pub mod clipboard;
+pub mod config;
 pub mod event;
+pub mod export;
+pub mod keys;
+#[cfg(target_os = "linux")]
+pub mod procfs;
+pub mod recording;
+pub mod theme;
 pub mod ui;
 
 /// Drain any keys already in the terminal input buffer (e.g. Enter from starting the app in
@@ -16,11 +29,26 @@ fn drain_pending_input() {
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
+    let cli = Cli::parse();
     color_eyre::install()?;
+    // Fails before the terminal is taken over if the file cannot be opened or read
+    let source = Source::open(&cli)?;
     crossterm::terminal::enable_raw_mode()?;
     drain_pending_input();
     let terminal = ratatui::init();
-    let result = App::new().run(terminal).await;
+    if !cli.no_mouse {
+        crossterm::execute!(std::io::stdout(), crossterm::event::EnableMouseCapture)?;
+        // The panic hook of ratatui::init restores the terminal but leaves the mouse captured
+        let restore_terminal = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = crossterm::execute!(std::io::stdout(), crossterm::event::DisableMouseCapture);
+            restore_terminal(info);
+        }));
+    }
+    let result = App::with_cli(&cli, source).run(terminal).await;
+    if !cli.no_mouse {
+        crossterm::execute!(std::io::stdout(), crossterm::event::DisableMouseCapture)?;
+    }
     crossterm::terminal::disable_raw_mode()?;
     ratatui::restore();
     result
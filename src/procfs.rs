@@ -0,0 +1,495 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    os::{fd::AsRawFd, unix::fs::MetadataExt},
+    path::Path,
+};
+
+use crate::app::Protocol;
+
+/// A socket read from one of the kernel tables below `/proc/net`.
+#[derive(Clone, Debug)]
+pub struct ProcSocket {
+    pub protocol: Protocol,
+    pub local_addr: IpAddr,
+    /// Local port. For raw sockets this is the IP protocol number, for ICMP sockets the echo id.
+    pub local_port: u16,
+    pub remote_addr: IpAddr,
+    pub remote_port: u16,
+    pub state: String,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+/// A network namespace and a process living in it.
+#[derive(Clone, Debug)]
+pub struct NetNamespace {
+    pub inode: u64,
+    /// Lowest PID in the namespace, used to read its socket tables from `/proc/<pid>/net`.
+    /// None for a namespace of `/run/netns` without processes.
+    pub pid: Option<u32>,
+    /// Name from `/run/netns`, if the namespace was created by `ip netns add`
+    pub name: Option<String>,
+}
+
+/// Inode of the network namespace of a process, e.g. `self` or a PID.
+pub fn net_namespace_inode(process: &str) -> Option<u64> {
+    let target = fs::read_link(format!("/proc/{process}/ns/net")).ok()?;
+    target
+        .to_string_lossy()
+        .strip_prefix("net:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Directory of the namespaces created by `ip netns add`
+const NAMED_NAMESPACES: &str = "/run/netns";
+
+/// All network namespaces with at least one process, found via `/proc/*/ns/net`, and the named
+/// ones of `/run/netns`.
+pub fn net_namespaces() -> Vec<NetNamespace> {
+    let mut namespaces: BTreeMap<u64, u32> = BTreeMap::new();
+    if let Ok(proc_dir) = fs::read_dir("/proc") {
+        for entry in proc_dir.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if let Some(inode) = net_namespace_inode(&pid.to_string()) {
+                let lowest = namespaces.entry(inode).or_insert(pid);
+                *lowest = (*lowest).min(pid);
+            }
+        }
+    }
+
+    let mut names = BTreeMap::new();
+    if let Ok(netns_dir) = fs::read_dir(NAMED_NAMESPACES) {
+        for entry in netns_dir.flatten() {
+            if let Ok(metadata) = fs::metadata(entry.path()) {
+                names.insert(
+                    metadata.ino(),
+                    entry.file_name().to_string_lossy().to_string(),
+                );
+            }
+        }
+    }
+
+    let mut namespaces: Vec<NetNamespace> = namespaces
+        .into_iter()
+        .map(|(inode, pid)| NetNamespace {
+            inode,
+            pid: Some(pid),
+            name: names.remove(&inode),
+        })
+        .collect();
+    namespaces.extend(names.into_iter().map(|(inode, name)| NetNamespace {
+        inode,
+        pid: None,
+        name: Some(name),
+    }));
+    namespaces
+}
+
+/// Read all sockets of `protocols` of the named namespace `name` by entering it on a helper
+/// thread, for namespaces without a process whose `/proc/<pid>/net` could be read.
+pub fn read_named_namespace_sockets(name: &str, protocols: &[Protocol]) -> Vec<ProcSocket> {
+    let Ok(namespace) = fs::File::open(Path::new(NAMED_NAMESPACES).join(name)) else {
+        return vec![];
+    };
+    let protocols = protocols.to_vec();
+    std::thread::spawn(move || {
+        // SAFETY: setns only changes the network namespace of this thread, which ends after
+        // reading the tables; the descriptor is valid while `namespace` is alive.
+        if unsafe { libc::setns(namespace.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+            return vec![];
+        }
+        // Unlike `/proc/self`, `/proc/thread-self` shows the namespace of this thread
+        let net_dir = Path::new("/proc/thread-self/net");
+        protocols
+            .into_iter()
+            .flat_map(|protocol| read_sockets(net_dir, protocol))
+            .collect()
+    })
+    .join()
+    .unwrap_or_default()
+}
+
+/// Read all sockets of `protocol` from a `net` directory, e.g. `/proc/net` or `/proc/<pid>/net`.
+pub fn read_sockets(net_dir: &Path, protocol: Protocol) -> Vec<ProcSocket> {
+    match protocol {
+        Protocol::Tcp => read_inet_tables(net_dir, protocol, &["tcp", "tcp6"]),
+        Protocol::Udp => read_inet_tables(net_dir, protocol, &["udp", "udp6"]),
+        Protocol::Raw => read_inet_tables(net_dir, protocol, &["raw", "raw6"]),
+        Protocol::Icmp => read_inet_tables(net_dir, protocol, &["icmp", "icmp6"]),
+        Protocol::Sctp => {
+            let mut sockets = read_sctp_assocs(&net_dir.join("sctp/assocs"));
+            sockets.extend(read_sctp_endpoints(&net_dir.join("sctp/eps")));
+            sockets
+        }
+    }
+}
+
+/// Map socket inodes to the PIDs holding a file descriptor on them, by scanning `/proc/*/fd`.
+pub fn socket_inode_pids() -> HashMap<u64, Vec<u32>> {
+    let mut inode_pids: HashMap<u64, Vec<u32>> = HashMap::new();
+    let Ok(proc_dir) = fs::read_dir("/proc") else {
+        return inode_pids;
+    };
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Some(inode) = fs::read_link(fd.path())
+                .ok()
+                .and_then(|target| parse_socket_link(&target.to_string_lossy()))
+            {
+                let pids = inode_pids.entry(inode).or_default();
+                if !pids.contains(&pid) {
+                    pids.push(pid);
+                }
+            }
+        }
+    }
+    inode_pids
+}
+
+/// File descriptors of a process and their link targets from `/proc/<pid>/fd`, sorted by number.
+pub fn read_fds(pid: u32) -> Vec<(u32, String)> {
+    let Ok(fds) = fs::read_dir(format!("/proc/{pid}/fd")) else {
+        return vec![];
+    };
+    let mut fds = fds
+        .flatten()
+        .filter_map(|fd| {
+            let number = fd.file_name().to_str()?.parse().ok()?;
+            let target = fs::read_link(fd.path()).ok()?;
+            Some((number, target.to_string_lossy().to_string()))
+        })
+        .collect::<Vec<_>>();
+    fds.sort_by_key(|(number, _)| *number);
+    fds
+}
+
+/// Nice value of a process, field 19 of `/proc/<pid>/stat`.
+pub fn read_nice(pid: u32) -> Option<i32> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name may contain spaces, so count fields after its closing parenthesis
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(16)?.parse().ok()
+}
+
+/// Parse the target of a `/proc/<pid>/fd/<n>` link like `socket:[12345]`.
+pub fn parse_socket_link(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Read tables sharing the `/proc/net/tcp` layout (tcp, udp, raw, icmp and their v6 variants).
+fn read_inet_tables(net_dir: &Path, protocol: Protocol, files: &[&str]) -> Vec<ProcSocket> {
+    let mut sockets = Vec::new();
+    for file in files {
+        if let Ok(content) = fs::read_to_string(net_dir.join(file)) {
+            sockets.extend(parse_inet_table(&content, protocol));
+        }
+    }
+    sockets
+}
+
+/// Parse a table with the `/proc/net/tcp` layout, including its header line.
+fn parse_inet_table(content: &str, protocol: Protocol) -> Vec<ProcSocket> {
+    let mut sockets = Vec::new();
+    for line in content.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            continue;
+        }
+        let (Some((local_addr, local_port)), Some((remote_addr, remote_port))) =
+            (parse_hex_addr_port(parts[1]), parse_hex_addr_port(parts[2]))
+        else {
+            continue;
+        };
+        let state = if protocol == Protocol::Tcp {
+            u8::from_str_radix(parts[3], 16)
+                .map(tcp_state_name)
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            String::new()
+        };
+        sockets.push(ProcSocket {
+            protocol,
+            local_addr,
+            local_port,
+            remote_addr,
+            remote_port,
+            state,
+            uid: parts[7].parse().unwrap_or(0),
+            inode: parts[9].parse().unwrap_or(0),
+        });
+    }
+    sockets
+}
+
+/// Read SCTP associations. Multi-homed associations are shown with their first addresses.
+fn read_sctp_assocs(path: &Path) -> Vec<ProcSocket> {
+    fs::read_to_string(path)
+        .map(|content| parse_sctp_assocs(&content))
+        .unwrap_or_default()
+}
+
+/// Parse the content of `/proc/net/sctp/assocs`, including its header line.
+fn parse_sctp_assocs(content: &str) -> Vec<ProcSocket> {
+    let mut sockets = Vec::new();
+    for line in content.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(separator) = parts.iter().position(|p| *p == "<->") else {
+            continue;
+        };
+        if separator < 14 {
+            continue;
+        }
+        let local_addr = parse_sctp_addr(parts[13]);
+        let remote_addr = parts
+            .get(separator + 1)
+            .map_or(unspecified(local_addr), |a| parse_sctp_addr(a));
+        sockets.push(ProcSocket {
+            protocol: Protocol::Sctp,
+            local_addr,
+            local_port: parts[11].parse().unwrap_or(0),
+            remote_addr,
+            remote_port: parts[12].parse().unwrap_or(0),
+            state: parts[4]
+                .parse()
+                .map(sctp_state_name)
+                .unwrap_or_default()
+                .to_string(),
+            uid: parts[9].parse().unwrap_or(0),
+            inode: parts[10].parse().unwrap_or(0),
+        });
+    }
+    sockets
+}
+
+/// Read SCTP endpoints, i.e. bound or listening SCTP sockets.
+fn read_sctp_endpoints(path: &Path) -> Vec<ProcSocket> {
+    fs::read_to_string(path)
+        .map(|content| parse_sctp_endpoints(&content))
+        .unwrap_or_default()
+}
+
+/// Parse the content of `/proc/net/sctp/eps`, including its header line.
+fn parse_sctp_endpoints(content: &str) -> Vec<ProcSocket> {
+    let mut sockets = Vec::new();
+    for line in content.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 9 {
+            continue;
+        }
+        let local_addr = parse_sctp_addr(parts[8]);
+        sockets.push(ProcSocket {
+            protocol: Protocol::Sctp,
+            local_addr,
+            local_port: parts[5].parse().unwrap_or(0),
+            remote_addr: unspecified(local_addr),
+            remote_port: 0,
+            state: if parts[3] == "10" {
+                "Listen".to_string()
+            } else {
+                String::new()
+            },
+            uid: parts[6].parse().unwrap_or(0),
+            inode: parts[7].parse().unwrap_or(0),
+        });
+    }
+    sockets
+}
+
+/// Parse an address like `0100007F:0035` as printed by the kernel in native byte order.
+fn parse_hex_addr_port(addr_port: &str) -> Option<(IpAddr, u16)> {
+    let (addr, port) = addr_port.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = match addr.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(
+            u32::from_str_radix(addr, 16).ok()?.to_ne_bytes(),
+        )),
+        32 => {
+            let mut octets = [0u8; 16];
+            for (i, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&addr[i * 8..(i + 1) * 8], 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some((ip, port))
+}
+
+/// Parse an SCTP address; the primary path of an association is prefixed with `*`.
+fn parse_sctp_addr(addr: &str) -> IpAddr {
+    addr.trim_start_matches('*')
+        .parse()
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
+
+/// The unspecified address of the same family as `addr`.
+fn unspecified(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    }
+}
+
+/// Names of the kernel TCP states, matching the `Debug` output of `netstat2::TcpState`.
+fn tcp_state_name(state: u8) -> &'static str {
+    match state {
+        0x01 => "Established",
+        0x02 => "SynSent",
+        0x03 => "SynReceived",
+        0x04 => "FinWait1",
+        0x05 => "FinWait2",
+        0x06 => "TimeWait",
+        0x07 => "Closed",
+        0x08 => "CloseWait",
+        0x09 => "LastAck",
+        0x0A => "Listen",
+        0x0B => "Closing",
+        _ => "Unknown",
+    }
+}
+
+/// Names of the SCTP association states (`sctp_state_t`).
+fn sctp_state_name(state: u8) -> &'static str {
+    match state {
+        0 => "Closed",
+        1 => "CookieWait",
+        2 => "CookieEchoed",
+        3 => "Established",
+        4 => "ShutdownPend",
+        5 => "ShutdownSent",
+        6 => "ShutdownRecv",
+        7 => "ShutdownAck",
+        _ => "Unknown",
+    }
+}
+
+// The kernel prints addresses in native byte order; the fixtures are from a little-endian host
+#[cfg(all(test, target_endian = "little"))]
+mod tests {
+    use super::*;
+
+    const INET_HEADER: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+
+    #[test]
+    fn parses_tcp6_rows() {
+        let content = format!(
+            "{INET_HEADER}\n   0: 00000000000000000000000001000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41215 1 0000000000000000 100 0 0 10 0\n   1: B80D0120000000000000000001000000:0016 B80D0120000000000000000002000000:C350 01 00000000:00000000 02:000A7F2D 00000000     0        0 52817 4 0000000000000000 20 4 30 10 -1\n"
+        );
+        let sockets = parse_inet_table(&content, Protocol::Tcp);
+        assert_eq!(sockets.len(), 2);
+        let listen = &sockets[0];
+        assert_eq!(listen.local_addr, "::1".parse::<IpAddr>().unwrap());
+        assert_eq!(listen.local_port, 8080);
+        assert_eq!(listen.remote_addr, "::".parse::<IpAddr>().unwrap());
+        assert_eq!(listen.state, "Listen");
+        assert_eq!(listen.uid, 1000);
+        assert_eq!(listen.inode, 41215);
+        let established = &sockets[1];
+        assert_eq!(
+            established.local_addr,
+            "2001:db8::1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(established.local_port, 22);
+        assert_eq!(
+            established.remote_addr,
+            "2001:db8::2".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(established.remote_port, 50000);
+        assert_eq!(established.state, "Established");
+    }
+
+    #[test]
+    fn parses_raw_and_icmp_rows_without_state() {
+        let raw = format!(
+            "{INET_HEADER}\n 255: 00000000:00FF 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 23456 2 0000000000000000 0\n"
+        );
+        let sockets = parse_inet_table(&raw, Protocol::Raw);
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].protocol, Protocol::Raw);
+        assert_eq!(sockets[0].local_addr, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        // The port of a raw socket is its IP protocol
+        assert_eq!(sockets[0].local_port, 255);
+        assert_eq!(sockets[0].state, "");
+        assert_eq!(sockets[0].inode, 23456);
+
+        let icmp = format!(
+            "{INET_HEADER}\n  12: 0100007F:0D05 00000000:0000 07 00000000:00000000 00:00000000 00000000  1000        0 34567 2 0000000000000000 0\n"
+        );
+        let sockets = parse_inet_table(&icmp, Protocol::Icmp);
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].local_addr, IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(sockets[0].local_port, 3333);
+        assert_eq!(sockets[0].state, "");
+        assert_eq!(sockets[0].uid, 1000);
+    }
+
+    #[test]
+    fn skips_malformed_inet_rows() {
+        let content = format!(
+            "{INET_HEADER}\n   0: 0100007F:0035\n   1: XYZ:0035 00000000:0000 0A 0 0 0 0 0 1\n"
+        );
+        assert!(parse_inet_table(&content, Protocol::Udp).is_empty());
+    }
+
+    #[test]
+    fn parses_sctp_associations_with_several_addresses() {
+        let content = " ASSOC     SOCK   STY SST ST HBKT ASSOC-ID TX_QUEUE RX_QUEUE UID INODE LPORT RPORT LADDRS <-> RADDRS HBINT INS OUTS MAXRT T1X T2X RTXC wmema wmemq sndbuf rcvbuf\n\
+ffff8800b6c8c000 ffff8800b6d8f800 2   1   3  0    1        0        0     0 23456  3868  5060  10.0.0.1 192.168.1.1 <-> *10.0.0.2 192.168.1.2 \t    7500    10    10   10    0    0        0        1        0   212992   212992\n";
+        let sockets = parse_sctp_assocs(content);
+        assert_eq!(sockets.len(), 1);
+        let assoc = &sockets[0];
+        assert_eq!(assoc.protocol, Protocol::Sctp);
+        // The first local address and the primary remote path are shown
+        assert_eq!(assoc.local_addr, "10.0.0.1".parse::<IpAddr>().unwrap());
+        assert_eq!(assoc.local_port, 3868);
+        assert_eq!(assoc.remote_addr, "10.0.0.2".parse::<IpAddr>().unwrap());
+        assert_eq!(assoc.remote_port, 5060);
+        assert_eq!(assoc.state, "Established");
+        assert_eq!(assoc.uid, 0);
+        assert_eq!(assoc.inode, 23456);
+    }
+
+    #[test]
+    fn parses_sctp_endpoints() {
+        let content = " ENDPT     SOCK   STY SST HBKT LPORT   UID INODE LADDRS\n\
+ffff88017e0a0200 ffff880299f7fa00 2   10  29   5000     0 29521 10.0.0.1 192.168.1.1\n\
+ffff88017e0a0400 ffff880299f7fc00 2   7   30   5001  1000 29522 fe80::1\n";
+        let sockets = parse_sctp_endpoints(content);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].local_addr, "10.0.0.1".parse::<IpAddr>().unwrap());
+        assert_eq!(sockets[0].local_port, 5000);
+        assert_eq!(sockets[0].remote_addr, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(sockets[0].state, "Listen");
+        assert_eq!(sockets[0].inode, 29521);
+        assert_eq!(sockets[1].local_addr, "fe80::1".parse::<IpAddr>().unwrap());
+        assert_eq!(sockets[1].remote_addr, IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+        assert_eq!(sockets[1].state, "");
+        assert_eq!(sockets[1].uid, 1000);
+    }
+}
@@ -0,0 +1,508 @@
+//! Recording refresh snapshots to a file and replaying them.
+//!
+//! A recording has one JSON object per line and refresh, so it can be appended to while
+//! netview runs and still be read if netview was stopped in the middle of a write. Process
+//! metadata is written only when a process appears or changes.
+
+use chrono::{DateTime, Local, TimeZone};
+use color_eyre::eyre::{WrapErr, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{File, OpenOptions},
+    io::Write,
+    net::IpAddr,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::app::Protocol;
+use crate::cli::Cli;
+
+/// A socket as read in a refresh, before filters, name resolution and rates are applied.
+/// Fields have short names and are left out if empty to keep recordings small.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SocketRecord {
+    #[serde(rename = "p")]
+    pub protocol: Protocol,
+    #[serde(rename = "la")]
+    pub local_addr: IpAddr,
+    #[serde(rename = "lp")]
+    pub local_port: u16,
+    /// None if the socket has no remote end
+    #[serde(rename = "ra", default, skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<IpAddr>,
+    #[serde(rename = "rp", default, skip_serializing_if = "is_zero")]
+    pub remote_port: u16,
+    #[serde(rename = "s", default, skip_serializing_if = "String::is_empty")]
+    pub state: String,
+    /// All processes holding the socket, sorted ascending
+    #[serde(rename = "o", default, skip_serializing_if = "Vec::is_empty")]
+    pub pids: Vec<u32>,
+    /// Name of the lowest PID's process; recorded once per process in [`Snapshot::processes`]
+    #[serde(skip)]
+    pub process: String,
+    #[serde(rename = "u", default, skip_serializing_if = "String::is_empty")]
+    pub user: String,
+    #[serde(rename = "ns", default, skip_serializing_if = "String::is_empty")]
+    pub namespace: String,
+    #[serde(rename = "cg", default, skip_serializing_if = "String::is_empty")]
+    pub cgroup: String,
+    #[serde(rename = "i", default, skip_serializing_if = "is_zero")]
+    pub inode: u64,
+    #[serde(rename = "rx", default, skip_serializing_if = "is_zero")]
+    pub rx_bytes: u64,
+    #[serde(rename = "tx", default, skip_serializing_if = "is_zero")]
+    pub tx_bytes: u64,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Metadata of a process owning a socket, or of one of its ancestors
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessRecord {
+    #[serde(rename = "n", default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Arguments joined by spaces
+    #[serde(rename = "c", default, skip_serializing_if = "String::is_empty")]
+    pub cmdline: String,
+    #[serde(rename = "e", default, skip_serializing_if = "String::is_empty")]
+    pub exe: String,
+    #[serde(rename = "u", default, skip_serializing_if = "String::is_empty")]
+    pub user: String,
+    #[serde(rename = "pp", default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u32>,
+    /// Unit, pod or container
+    #[serde(rename = "cg", default, skip_serializing_if = "String::is_empty")]
+    pub cgroup: String,
+}
+
+/// All sockets of one refresh
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    /// Milliseconds since the Unix epoch
+    #[serde(rename = "t")]
+    pub time_ms: i64,
+    #[serde(rename = "s")]
+    pub sockets: Vec<SocketRecord>,
+    /// Processes by PID. A recording only has the processes that are new or changed since the
+    /// snapshot before; [`Replay::process`] finds the latest record.
+    #[serde(rename = "p", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub processes: BTreeMap<u32, ProcessRecord>,
+}
+
+impl Snapshot {
+    pub fn time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_millis_opt(self.time_ms)
+            .single()
+            .unwrap_or_default()
+    }
+}
+
+/// Where the shown connections come from
+#[derive(Debug)]
+pub enum Source {
+    /// The sockets of this system, appended to a recording if there is one
+    Live(Option<Recorder>),
+    /// A recording
+    Replay(Replay),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Live(None)
+    }
+}
+
+impl Source {
+    /// Opens the recording or replay file given on the command line
+    pub fn open(cli: &Cli) -> color_eyre::Result<Self> {
+        if let Some(path) = &cli.replay {
+            return Ok(Source::Replay(Replay::load(path)?));
+        }
+        cli.record
+            .as_deref()
+            .map(Recorder::create)
+            .transpose()
+            .map(Source::Live)
+    }
+}
+
+/// Appends snapshots to a recording file
+#[derive(Debug)]
+pub struct Recorder {
+    file: File,
+    /// Processes as last written, so unchanged ones are not written again
+    processes: HashMap<u32, ProcessRecord>,
+    /// When the last snapshot was written
+    pub last_write: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> color_eyre::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .wrap_err_with(|| format!("Cannot open {} for recording", path.display()))?;
+        Ok(Self {
+            file,
+            processes: HashMap::new(),
+            last_write: None,
+        })
+    }
+
+    /// Appends `snapshot` with the processes that changed since the last write
+    pub fn write(&mut self, snapshot: &Snapshot) -> color_eyre::Result<()> {
+        let processes = snapshot
+            .processes
+            .iter()
+            .filter(|(pid, process)| self.processes.get(pid) != Some(process))
+            .map(|(pid, process)| (*pid, process.clone()))
+            .collect();
+        let changes = Snapshot {
+            time_ms: snapshot.time_ms,
+            sockets: snapshot.sockets.clone(),
+            processes,
+        };
+        let mut line = serde_json::to_string(&changes)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.processes.extend(changes.processes);
+        self.last_write = Some(Instant::now());
+        Ok(())
+    }
+}
+
+/// Slowest and fastest replay speed as powers of two
+const SPEED_EXPONENTS: std::ops::RangeInclusive<i32> = -4..=6;
+
+/// A recording being played
+#[derive(Debug)]
+pub struct Replay {
+    snapshots: Vec<Snapshot>,
+    /// Index of the shown snapshot
+    position: usize,
+    /// Playing speed is 2 to this power
+    speed_exponent: i32,
+    /// When the shown snapshot was shown, to time the next one
+    shown_at: Instant,
+    /// Stands for the time of the first snapshot, so rates are measured in recorded time
+    start: Instant,
+}
+
+impl Replay {
+    /// Reads a recording; an incomplete last line, e.g. of an interrupted recording, is skipped
+    pub fn load(path: &Path) -> color_eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Cannot read recording {}", path.display()))?;
+        let lines: Vec<&str> = content.lines().filter(|line| !line.is_empty()).collect();
+        let mut snapshots: Vec<Snapshot> = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            match serde_json::from_str(line) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(_) if index + 1 == lines.len() => {}
+                Err(error) => bail!("{}, line {}: {error}", path.display(), index + 1),
+            }
+        }
+        if snapshots.is_empty() {
+            bail!("{} contains no snapshots", path.display());
+        }
+        // Process names are shown for each socket; a process is recorded when it first appears
+        let mut names: HashMap<u32, String> = HashMap::new();
+        for snapshot in &mut snapshots {
+            for (pid, process) in &snapshot.processes {
+                names.insert(*pid, process.name.clone());
+            }
+            for socket in &mut snapshot.sockets {
+                if let Some(name) = socket.pids.first().and_then(|pid| names.get(pid)) {
+                    socket.process = name.clone();
+                }
+            }
+        }
+        let now = Instant::now();
+        Ok(Self {
+            snapshots,
+            position: 0,
+            speed_exponent: 0,
+            shown_at: now,
+            start: now,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn snapshot(&self, index: usize) -> &Snapshot {
+        &self.snapshots[index]
+    }
+
+    /// Metadata of process `pid` as recorded up to snapshot `index`
+    pub fn process(&self, index: usize, pid: u32) -> Option<&ProcessRecord> {
+        self.snapshots[..=index]
+            .iter()
+            .rev()
+            .find_map(|snapshot| snapshot.processes.get(&pid))
+    }
+
+    /// Owners of the sockets of snapshot `index` and their ancestors, as recorded by then
+    pub fn processes(&self, index: usize) -> BTreeMap<u32, &ProcessRecord> {
+        let mut processes = BTreeMap::new();
+        let mut pending: Vec<u32> = self.snapshots[index]
+            .sockets
+            .iter()
+            .flat_map(|socket| socket.pids.iter().copied())
+            .collect();
+        while let Some(pid) = pending.pop() {
+            if processes.contains_key(&pid) {
+                continue;
+            }
+            if let Some(process) = self.process(index, pid) {
+                pending.extend(process.parent);
+                processes.insert(pid, process);
+            }
+        }
+        processes
+    }
+
+    /// The time of snapshot `index` on the clock of this replay
+    pub fn instant(&self, index: usize) -> Instant {
+        let elapsed = self.snapshots[index].time_ms - self.snapshots[0].time_ms;
+        self.start + Duration::from_millis(elapsed.max(0) as u64)
+    }
+
+    /// Makes `index` the shown snapshot, starting its display time now
+    pub fn seek(&mut self, index: usize) {
+        self.position = index.min(self.snapshots.len() - 1);
+        self.shown_at = Instant::now();
+    }
+
+    /// The snapshot to show at `now` while playing: the last one whose recorded time has passed
+    pub fn due(&self, now: Instant) -> usize {
+        let played = now.saturating_duration_since(self.shown_at);
+        let played = played.mul_f64(2f64.powi(self.speed_exponent));
+        let until = self.snapshots[self.position].time_ms + played.as_millis() as i64;
+        let mut index = self.position;
+        while index + 1 < self.snapshots.len() && self.snapshots[index + 1].time_ms <= until {
+            index += 1;
+        }
+        index
+    }
+
+    pub fn change_speed(&mut self, faster: bool) {
+        let exponent = self.speed_exponent + if faster { 1 } else { -1 };
+        self.speed_exponent = exponent.clamp(*SPEED_EXPONENTS.start(), *SPEED_EXPONENTS.end());
+    }
+
+    /// Playing speed, e.g. "4x" or "1/2x"
+    pub fn speed_label(&self) -> String {
+        if self.speed_exponent >= 0 {
+            format!("{}x", 1 << self.speed_exponent)
+        } else {
+            format!("1/{}x", 1 << -self.speed_exponent)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A file in the temp directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("netview-test-{}-{name}.jsonl", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn socket(local_port: u16, pids: Vec<u32>) -> SocketRecord {
+        SocketRecord {
+            protocol: Protocol::Tcp,
+            local_addr: "127.0.0.1".parse().unwrap(),
+            local_port,
+            remote_addr: None,
+            remote_port: 0,
+            state: "Listen".to_string(),
+            pids,
+            process: String::new(),
+            user: "root".to_string(),
+            namespace: String::new(),
+            cgroup: String::new(),
+            inode: 1,
+            rx_bytes: 0,
+            tx_bytes: 0,
+        }
+    }
+
+    fn process(name: &str, parent: Option<u32>) -> ProcessRecord {
+        ProcessRecord {
+            name: name.to_string(),
+            cmdline: format!("/usr/bin/{name} --flag"),
+            parent,
+            ..ProcessRecord::default()
+        }
+    }
+
+    fn snapshot(
+        time_ms: i64,
+        sockets: Vec<SocketRecord>,
+        processes: &[(u32, ProcessRecord)],
+    ) -> Snapshot {
+        Snapshot {
+            time_ms,
+            sockets,
+            processes: processes.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let file = TempFile::new("round-trip");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        let init = (1, process("init", None));
+        let nginx = (100, process("nginx", Some(1)));
+        recorder
+            .write(&snapshot(
+                1_000,
+                vec![socket(80, vec![100])],
+                &[init.clone(), nginx.clone()],
+            ))
+            .unwrap();
+        recorder
+            .write(&snapshot(
+                2_000,
+                vec![socket(80, vec![100]), socket(443, vec![100])],
+                &[init, nginx],
+            ))
+            .unwrap();
+
+        let replay = Replay::load(&file.0).unwrap();
+        assert_eq!(replay.len(), 2);
+        let second = replay.snapshot(1);
+        assert_eq!(second.time_ms, 2_000);
+        let ports: Vec<u16> = second
+            .sockets
+            .iter()
+            .map(|socket| socket.local_port)
+            .collect();
+        assert_eq!(ports, [80, 443]);
+        assert_eq!(second.sockets[1].state, "Listen");
+        assert_eq!(second.sockets[1].user, "root");
+        // Names are filled in from the process records
+        assert_eq!(second.sockets[1].process, "nginx");
+        assert_eq!(
+            replay.instant(1) - replay.instant(0),
+            Duration::from_secs(1)
+        );
+        let processes = replay.processes(1);
+        assert_eq!(processes.keys().copied().collect::<Vec<_>>(), [1, 100]);
+    }
+
+    #[test]
+    fn writes_process_metadata_only_when_changed() {
+        let file = TempFile::new("changes");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        let sockets = vec![socket(80, vec![100])];
+        recorder
+            .write(&snapshot(
+                1_000,
+                sockets.clone(),
+                &[(100, process("nginx", None))],
+            ))
+            .unwrap();
+        recorder
+            .write(&snapshot(
+                2_000,
+                sockets.clone(),
+                &[(100, process("nginx", None))],
+            ))
+            .unwrap();
+        recorder
+            .write(&snapshot(
+                3_000,
+                sockets,
+                &[(100, process("nginx-worker", None))],
+            ))
+            .unwrap();
+
+        let replay = Replay::load(&file.0).unwrap();
+        assert_eq!(replay.snapshot(0).processes.len(), 1);
+        assert!(replay.snapshot(1).processes.is_empty());
+        assert_eq!(replay.snapshot(2).processes[&100].name, "nginx-worker");
+    }
+
+    #[test]
+    fn finds_the_latest_process_record() {
+        let file = TempFile::new("latest");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        let sockets = vec![socket(80, vec![100])];
+        for (time_ms, name) in [(1_000, "old"), (2_000, "old"), (3_000, "new")] {
+            recorder
+                .write(&snapshot(
+                    time_ms,
+                    sockets.clone(),
+                    &[(100, process(name, None))],
+                ))
+                .unwrap();
+        }
+
+        let replay = Replay::load(&file.0).unwrap();
+        assert_eq!(replay.process(1, 100).unwrap().name, "old");
+        assert_eq!(replay.process(2, 100).unwrap().name, "new");
+        assert_eq!(replay.snapshot(1).sockets[0].process, "old");
+        assert!(replay.process(2, 200).is_none());
+    }
+
+    #[test]
+    fn skips_a_truncated_last_line() {
+        let file = TempFile::new("truncated");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        recorder
+            .write(&snapshot(1_000, vec![socket(80, vec![])], &[]))
+            .unwrap();
+        recorder.file.write_all(br#"{"t":2000,"s":[{"p":"#).unwrap();
+
+        let replay = Replay::load(&file.0).unwrap();
+        assert_eq!(replay.len(), 1);
+    }
+
+    #[test]
+    fn rejects_a_bad_line_in_the_middle() {
+        let file = TempFile::new("bad");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        recorder
+            .write(&snapshot(1_000, vec![socket(80, vec![])], &[]))
+            .unwrap();
+        recorder.file.write_all(b"not json\n").unwrap();
+        recorder
+            .write(&snapshot(2_000, vec![socket(80, vec![])], &[]))
+            .unwrap();
+
+        let error = Replay::load(&file.0).unwrap_err().to_string();
+        assert!(error.contains("line 2:"), "{error}");
+    }
+}
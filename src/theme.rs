@@ -0,0 +1,253 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Names of the built-in themes, in the order `t` cycles through them
+pub const BUILT_IN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// Colors of the user interface. Unset colors keep the terminal's default.
+///
+/// User themes are defined in the config file, e.g.
+///
+/// ```toml
+/// theme = "ocean"
+///
+/// [themes.ocean]
+/// sorted_column = "light-blue"
+/// selected_bg = "#003366"
+///
+/// [themes.ocean.states]
+/// TimeWait = "dark-gray"
+/// ```
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Theme {
+    /// Normal text
+    pub text: Option<Color>,
+    pub background: Option<Color>,
+    /// Table headers, in addition to bold text
+    pub header: Option<Color>,
+    /// Values of the column the table is sorted by
+    pub sorted_column: Option<Color>,
+    /// Selected row; the row is shown in reverse video if neither is set
+    pub selected_fg: Option<Color>,
+    pub selected_bg: Option<Color>,
+    /// Key hints and other secondary text
+    pub hint: Option<Color>,
+    /// Names in key/value lists and the memory chart
+    pub accent: Option<Color>,
+    /// CPU chart
+    pub chart: Option<Color>,
+    /// Error messages in the status bar, in addition to bold text
+    pub error: Option<Color>,
+    /// Text color of connections by state, e.g. `TimeWait` or `SynSent`
+    pub states: BTreeMap<String, Color>,
+    /// States shown dimmed, e.g. sockets that are about to disappear
+    pub dimmed_states: Vec<String>,
+    /// Rows of closed connections kept in a frozen row order, in addition to crossed out text
+    pub closed: Option<Color>,
+    /// Text matching the search; underlined if neither is set
+    pub match_fg: Option<Color>,
+    pub match_bg: Option<Color>,
+    /// Marked rows, in addition to bold text; italic if unset
+    pub marked: Option<Color>,
+}
+
+impl Theme {
+    /// The built-in theme `name`, if there is one
+    pub fn built_in(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            sorted_column: Some(Color::Green),
+            hint: Some(Color::DarkGray),
+            accent: Some(Color::Cyan),
+            chart: Some(Color::Green),
+            error: Some(Color::Red),
+            closed: Some(Color::DarkGray),
+            match_fg: Some(Color::Black),
+            match_bg: Some(Color::Yellow),
+            marked: Some(Color::LightMagenta),
+            states: states(&[
+                ("Listen", Color::Cyan),
+                ("SynSent", Color::Yellow),
+                ("SynReceived", Color::Yellow),
+                ("CloseWait", Color::Magenta),
+                ("TimeWait", Color::DarkGray),
+            ]),
+            dimmed_states: dimmed_states(),
+            ..Theme::default()
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            sorted_column: Some(Color::Blue),
+            hint: Some(Color::Gray),
+            accent: Some(Color::Magenta),
+            chart: Some(Color::Blue),
+            error: Some(Color::Red),
+            closed: Some(Color::Gray),
+            match_fg: Some(Color::Black),
+            match_bg: Some(Color::LightYellow),
+            marked: Some(Color::Magenta),
+            states: states(&[
+                ("Listen", Color::Blue),
+                ("SynSent", Color::Rgb(0x99, 0x66, 0x00)),
+                ("SynReceived", Color::Rgb(0x99, 0x66, 0x00)),
+                ("CloseWait", Color::Red),
+                ("TimeWait", Color::Gray),
+            ]),
+            dimmed_states: dimmed_states(),
+            ..Theme::default()
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            text: Some(Color::White),
+            background: Some(Color::Black),
+            header: Some(Color::LightYellow),
+            sorted_column: Some(Color::LightGreen),
+            selected_fg: Some(Color::Black),
+            selected_bg: Some(Color::LightCyan),
+            hint: Some(Color::White),
+            accent: Some(Color::LightCyan),
+            chart: Some(Color::LightGreen),
+            error: Some(Color::LightRed),
+            states: states(&[
+                ("SynSent", Color::LightYellow),
+                ("SynReceived", Color::LightYellow),
+                ("CloseWait", Color::LightRed),
+            ]),
+            dimmed_states: vec![],
+            closed: None,
+            match_fg: Some(Color::Black),
+            match_bg: Some(Color::LightYellow),
+            marked: Some(Color::LightMagenta),
+        }
+    }
+
+    /// Theme for `NO_COLOR`: only bold, dim and reverse video are used.
+    pub fn no_color() -> Theme {
+        Theme {
+            dimmed_states: dimmed_states(),
+            ..Theme::default()
+        }
+    }
+
+    pub fn normal(&self) -> Style {
+        with_colors(Style::default(), self.text, self.background)
+    }
+
+    pub fn header(&self) -> Style {
+        with_colors(
+            Style::default().add_modifier(Modifier::BOLD),
+            self.header,
+            None,
+        )
+    }
+
+    pub fn sorted_column(&self) -> Style {
+        with_colors(Style::default(), self.sorted_column, None)
+    }
+
+    pub fn selected(&self) -> Style {
+        if self.selected_fg.is_none() && self.selected_bg.is_none() {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            with_colors(Style::default(), self.selected_fg, self.selected_bg)
+        }
+    }
+
+    pub fn hint(&self) -> Style {
+        with_colors(Style::default(), self.hint, None)
+    }
+
+    pub fn accent(&self) -> Style {
+        with_colors(Style::default(), self.accent, None)
+    }
+
+    pub fn chart(&self) -> Style {
+        with_colors(Style::default(), self.chart, None)
+    }
+
+    pub fn error(&self) -> Style {
+        with_colors(
+            Style::default().add_modifier(Modifier::BOLD),
+            self.error,
+            None,
+        )
+    }
+
+    pub fn search_match(&self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD);
+        if self.match_fg.is_none() && self.match_bg.is_none() {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            with_colors(style, self.match_fg, self.match_bg)
+        }
+    }
+
+    pub fn marked(&self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD);
+        match self.marked {
+            Some(color) => style.fg(color),
+            None => style.add_modifier(Modifier::ITALIC),
+        }
+    }
+
+    pub fn closed(&self) -> Style {
+        with_colors(
+            Style::default().add_modifier(Modifier::CROSSED_OUT),
+            self.closed,
+            None,
+        )
+    }
+
+    /// Style of a connection row in `state`
+    pub fn state(&self, state: &str) -> Style {
+        let mut style = with_colors(Style::default(), self.states.get(state).copied(), None);
+        if self.dimmed_states.iter().any(|dimmed| dimmed == state) {
+            style = style.add_modifier(Modifier::DIM);
+        }
+        style
+    }
+}
+
+fn with_colors(mut style: Style, fg: Option<Color>, bg: Option<Color>) -> Style {
+    if let Some(fg) = fg {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = bg {
+        style = style.bg(bg);
+    }
+    style
+}
+
+fn states(colors: &[(&str, Color)]) -> BTreeMap<String, Color> {
+    colors
+        .iter()
+        .map(|(state, color)| (state.to_string(), *color))
+        .collect()
+}
+
+/// TCP states of closing connections
+fn dimmed_states() -> Vec<String> {
+    ["TimeWait", "FinWait1", "FinWait2", "LastAck", "Closing"]
+        .iter()
+        .map(|state| state.to_string())
+        .collect()
+}
+
+/// True, if colors are disabled by a non-empty `NO_COLOR` variable (see no-color.org)
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
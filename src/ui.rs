@@ -2,125 +2,277 @@ use chrono::DateTime;
 use humantime::format_duration;
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, Widget, Wrap},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, Tabs, Widget,
+    },
 };
 use std::time::Duration;
-use sysinfo::Pid;
+use sysinfo::{Pid, System, Users};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, SortColumn, SortOrder};
+use crate::app::{
+    App, BulkAction, ConnectionEntry, CopyField, EnvironmentSort, IpVersionFilter, ProcessTab,
+    Protocol, SortColumn, SortOrder, UserFilter,
+};
+use crate::export::ExportFormat;
+use crate::keys::{Action, KeyContext};
+use crate::recording::{Replay, Source};
 
 impl Widget for &App {
     /// Renders the user interface widgets.
     fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.theme.normal());
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+        let (area, status_area) = (layout[0], layout[1]);
+        self.render_status_bar(status_area, buf);
         match self.ui_state {
             crate::app::UiState::ConnectionTable => self.render_connection_table(area, buf),
             crate::app::UiState::Help => self.render_help_overlay(area, buf),
             crate::app::UiState::ProcessInfo => self.render_process_info(area, buf),
+            crate::app::UiState::ProtocolFilter => self.render_protocol_filter(area, buf),
+            crate::app::UiState::UserFilter => self.render_user_filter(area, buf),
+            crate::app::UiState::NamespaceFilter => self.render_namespace_filter(area, buf),
+            crate::app::UiState::CgroupFilter => self.render_cgroup_filter(area, buf),
+            crate::app::UiState::ColumnChooser => self.render_column_chooser(area, buf),
+            crate::app::UiState::BulkActions => self.render_bulk_actions(area, buf),
+            crate::app::UiState::CopyMenu => self.render_copy_menu(area, buf),
+            crate::app::UiState::ExportMenu => self.render_export_menu(area, buf),
+            crate::app::UiState::ConfirmSignal => self.render_signal_confirmation(area, buf),
         }
     }
 }
 
 impl App {
-    fn entries_to_rows(&self) -> Vec<Row<'_>> {
+    /// Bottom line with connection counts, active filters, throughput and refresh time. A
+    /// transient message replaces the counts while it is shown.
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let left = match &self.status_message {
+            Some(message) => Span::styled(
+                format!(" {}", message.text),
+                if message.is_error {
+                    self.theme.error()
+                } else {
+                    self.theme.accent()
+                },
+            ),
+            None if self.table_search_active => Span::raw(format!(" /{}_", self.table_search)),
+            None if !self.table_search.is_empty() => {
+                let matches = self
+                    .entries
+                    .iter()
+                    .filter(|entry| self.matches_search(entry))
+                    .count();
+                Span::raw(format!(
+                    " /{}  {matches} matches, n/N for next/previous",
+                    self.table_search
+                ))
+            }
+            None => Span::raw(format!(" {}", self.connection_counts())),
+        };
+
+        let mut filters = vec![
+            match self.ip_version_filter {
+                IpVersionFilter::Ipv4Only => "IPv4",
+                IpVersionFilter::Ipv6Only => "IPv6",
+                IpVersionFilter::Ipv4AndIpv6 => "IPv4+IPv6",
+            }
+            .to_string(),
+            match self.protocol_filter.summary().as_str() {
+                "all" => "all protocols".to_string(),
+                shown => shown.to_string(),
+            },
+            match (self.resolve_address_names, self.sort_addresses_by_name) {
+                (true, true) => "DNS on, sorted by name",
+                (true, false) => "DNS on",
+                (false, _) => "DNS off",
+            }
+            .to_string(),
+        ];
+        match &self.user_filter {
+            UserFilter::AllUsers => {}
+            UserFilter::CurrentUser => filters.push(format!("user {}", self.current_user)),
+            UserFilter::User(name) => filters.push(format!("user {name}")),
+        }
+        filters.extend(
+            self.namespace_filter
+                .iter()
+                .map(|name| format!("ns {name}")),
+        );
+        filters.extend(self.cgroup_filter.iter().cloned());
+        let (rx, tx) = self.throughput;
+        let refresh = match (&self.source, self.last_refresh) {
+            (Source::Replay(replay), _) => format!(
+                "replay {}/{} {} {}{}",
+                replay.position() + 1,
+                replay.len(),
+                replay
+                    .snapshot(replay.position())
+                    .time()
+                    .format("%Y-%m-%d %H:%M:%S"),
+                replay.speed_label(),
+                if self.paused { " paused" } else { "" }
+            ),
+            _ if self.paused => "paused".to_string(),
+            (Source::Live(recorder), Some((time, duration))) => format!(
+                "refreshed {} in {} ms{}",
+                time.format("%H:%M:%S"),
+                duration.as_millis(),
+                if recorder.is_some() {
+                    ", recording"
+                } else {
+                    ""
+                }
+            ),
+            (Source::Live(_), None) => String::new(),
+        };
+        // Sections on the right, dropped from the front if the terminal is too narrow
+        let mut right = vec![
+            filters.join(" "),
+            format!("↓ {} ↑ {}", self.format_rate(rx), self.format_rate(tx)),
+            refresh,
+        ];
+        right.retain(|section| !section.is_empty());
+        let right_text = loop {
+            let text = format!("{} ", right.join(" │ "));
+            if right.is_empty() || left.width() + text.width() < area.width as usize {
+                break text;
+            }
+            right.remove(0);
+        };
+
+        buf.set_style(area, self.theme.hint());
+        buf.set_span(area.x, area.y, &left, area.width);
+        let right_width = right_text.width() as u16;
+        if right_width <= area.width {
+            buf.set_string(
+                area.right() - right_width,
+                area.y,
+                right_text,
+                self.theme.hint(),
+            );
+        }
+    }
+
+    /// Shown vs. all sockets, by protocol, and the most frequent states of the shown connections
+    fn connection_counts(&self) -> String {
+        let total: usize = self.connection_totals.values().sum();
+        let open = self.entries.iter().filter(|entry| !entry.closed).count();
+        let mut text = format!("{open} of {total} sockets");
+        for protocol in Protocol::ALL {
+            let Some(protocol_total) = self.connection_totals.get(protocol.label()) else {
+                continue;
+            };
+            let shown = self
+                .entries
+                .iter()
+                .filter(|entry| !entry.closed && entry.proto.starts_with(protocol.label()))
+                .count();
+            text.push_str(&format!(" · {} {shown}/{protocol_total}", protocol.label()));
+        }
+        let mut states: Vec<(&str, usize)> = Vec::new();
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| !entry.closed && !entry.state.is_empty())
+        {
+            match states.iter_mut().find(|(state, _)| *state == entry.state) {
+                Some((_, count)) => *count += 1,
+                None => states.push((&entry.state, 1)),
+            }
+        }
+        states.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        for (state, count) in states.iter().take(4) {
+            text.push_str(&format!(" · {state} {count}"));
+        }
+        let marked = self.marked_entries().len();
+        if marked > 0 {
+            text.push_str(&format!(" · {marked} marked"));
+        }
+        text
+    }
+
+    fn entries_to_rows(&self, columns: &[(TableColumn, u16)]) -> Vec<Row<'_>> {
+        let row_height = columns
+            .iter()
+            .map(|(column, _)| column.lines.len())
+            .max()
+            .unwrap_or(1) as u16;
+        let visual_range = self.visual_range();
         self.entries
             .iter()
-            .map(|e| {
-                let sorted_column_style = Style::default().fg(Color::Green);
-                let selected_row_style = Style::default().add_modifier(Modifier::REVERSED);
-                let normal = Style::default();
+            .enumerate()
+            .map(|(index, e)| {
+                let mut normal = if e.closed {
+                    self.theme.closed()
+                } else {
+                    self.theme.state(&e.state)
+                };
+                if visual_range
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&index))
+                    || self.marked.contains(&e.id())
+                {
+                    normal = normal.patch(self.theme.marked());
+                }
+                let sorted_column_style = normal.patch(self.theme.sorted_column());
+                let selected_row_style = self.theme.selected();
 
-                let cells = vec![
-                    Cell::from(e.proto.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::Proto {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.local_ip.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::LocalIP {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.local_port.to_string()).style(
-                        if Some(e) == self.selected.as_ref() {
+                let search = if self.matches_search(e) {
+                    self.table_search.as_str()
+                } else {
+                    ""
+                };
+                let cells = columns
+                    .iter()
+                    .map(|(column, width)| {
+                        let text = column.text(e, *width);
+                        let text = if column.is_searched() {
+                            highlight_matches(&text, search, self.theme.search_match())
+                        } else {
+                            Text::from(text)
+                        };
+                        Cell::from(text).style(if Some(e) == self.selected.as_ref() {
                             selected_row_style
-                        } else if self.sort_column == SortColumn::LocalPort {
+                        } else if column.contains(self.sort_column) {
                             sorted_column_style
                         } else {
                             normal
-                        },
-                    ),
-                    Cell::from(e.remote_ip.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::RemoteIP {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(if e.remote_port != 0 {
-                        e.remote_port.to_string()
-                    } else {
-                        "".to_string()
+                        })
                     })
-                    .style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::RemotePort {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.state.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::State {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.pid.to_string()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::PID {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.process.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::Process {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                    Cell::from(e.data_rate.clone()).style(if Some(e) == self.selected.as_ref() {
-                        selected_row_style
-                    } else if self.sort_column == SortColumn::DataRate {
-                        sorted_column_style
-                    } else {
-                        normal
-                    }),
-                ];
-                Row::new(cells)
+                    .collect::<Vec<_>>();
+                Row::new(cells).height(row_height)
             })
             .collect()
     }
 
     fn render_connection_table(&self, area: Rect, buf: &mut Buffer) {
-        let table_height = area.height as usize;
-        let visible_table_height = table_height.saturating_sub(2);
+        let columns = self.table_layout(area.width.saturating_sub(2));
+        let row_height = columns
+            .iter()
+            .map(|(column, _)| column.lines.len())
+            .max()
+            .unwrap_or(1);
+        // One line more than the rows fitting between the borders and the header
+        let visible_table_height =
+            (area.height as usize).saturating_sub(2 + row_height) / row_height + 1;
         self.visible_table_height.set(visible_table_height);
 
-        let rows = self.entries_to_rows();
-        let header = render_connections_header(self.sort_column, self.sort_order);
+        let rows = self.entries_to_rows(&columns);
+        let header = render_connections_header(&columns, &self.sort_keys(), self.theme.header());
 
-        let connections_title = if self.paused {
-            "Connections (paused - press 'SPACE' to resume)"
-        } else {
-            "Connections (live - press 'SPACE' to pause)"
+        let connections_title = match (self.paused, self.freeze_order) {
+            (true, _) if self.is_replay() => "Replay (paused - SPACE plays, '.' and ',' step)",
+            (false, _) if self.is_replay() => "Replay (SPACE pauses, '+' and '-' change speed)",
+            (true, _) => "Connections (paused - press 'SPACE' to resume)",
+            (false, true) => "Connections (live, order frozen - press 'f' to sort)",
+            (false, false) => "Connections (live - press 'SPACE' to pause)",
         };
 
         if let Some(index) = self.selected_index {
@@ -131,106 +283,175 @@ impl App {
                     .set(index - (visible_table_height - 1) + 1);
             }
         };
-        let rows_to_show = &rows[self.scroll_connection_table.get()
-            ..(self.scroll_connection_table.get() + visible_table_height).min(rows.len())];
-        let table = Table::new(
-            rows_to_show.iter().cloned(),
-            [
-                Constraint::Length(7),  // Proto
-                Constraint::Length(40), // Local IP
-                Constraint::Length(5),  // Local Port
-                Constraint::Length(40), // Remote IP
-                Constraint::Length(5),  // Remote Port
-                Constraint::Length(11), // State
-                Constraint::Length(7),  // PID
-                Constraint::Length(25), // Process
-                Constraint::Length(10), // Rate
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .title(connections_title)
-                .borders(Borders::ALL),
-        );
+        self.record_click_areas(area, &columns, row_height);
+        // Filters may have removed rows since the scroll position was set
+        let first = self.scroll_connection_table.get().min(rows.len());
+        let rows_to_show = &rows[first..(first + visible_table_height).min(rows.len())];
+        let widths = columns.iter().map(|(_, width)| Constraint::Length(*width));
+        let table = Table::new(rows_to_show.iter().cloned(), widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(connections_title)
+                    .borders(Borders::ALL),
+            );
 
         table.render(area, buf);
     }
 
+    /// Remembers where the header cells and rows of the connection table are drawn, so mouse
+    /// clicks can be mapped to columns and connections.
+    fn record_click_areas(&self, area: Rect, columns: &[(TableColumn, u16)], row_height: usize) {
+        let inner = area.inner(Margin::new(1, 1));
+        let mut header_click_areas = self.header_click_areas.borrow_mut();
+        header_click_areas.clear();
+        // Columns are laid out left to right, separated by the table's column spacing of one
+        let mut x = inner.x;
+        for (column, width) in columns {
+            for (line, fields) in column.lines.iter().enumerate() {
+                let cell = Rect::new(x, inner.y + line as u16, *width, 1).intersection(inner);
+                header_click_areas.push((cell, fields[0]));
+            }
+            x = x.saturating_add(width + 1);
+        }
+        self.connection_row_height.set(row_height);
+        self.connection_rows_area.set(Rect {
+            y: inner.y + row_height as u16,
+            height: inner.height.saturating_sub(row_height as u16),
+            ..inner
+        });
+    }
+
+    /// Lists the active key bindings, grouped by where they apply
     fn render_help_overlay(&self, area: Rect, buf: &mut Buffer) {
         self.render_connection_table(area, buf);
-        let help_width = 46u16;
-        let help_height = 20u16;
-        let vertical = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),
-                Constraint::Length(help_height),
-                Constraint::Min(0),
-            ])
-            .split(area);
-        let horizontal = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Min(0),
-                Constraint::Length(help_width),
-                Constraint::Min(0),
-            ])
-            .split(vertical[1]);
-        let help_area = horizontal[1];
-        let overlay_style = Style::default();
-        Clear.render(help_area, buf);
-        for y in help_area.top()..help_area.bottom() {
-            buf.set_string(
-                help_area.x,
-                y,
-                " ".repeat(help_area.width as usize),
-                overlay_style,
-            );
+        let overlay_style = self.theme.normal();
+        let sections: Vec<(KeyContext, Vec<(String, String)>)> = KeyContext::ALL
+            .iter()
+            .map(|context| {
+                let actions: Vec<(String, String)> = self
+                    .key_bindings
+                    .actions(*context)
+                    .into_iter()
+                    .map(|(action, chords)| {
+                        let keys = chords.iter().map(ToString::to_string).collect::<Vec<_>>();
+                        (keys.join(", "), action.description())
+                    })
+                    .collect();
+                (*context, actions)
+            })
+            .filter(|(_, actions)| !actions.is_empty())
+            .collect();
+        let key_width = sections
+            .iter()
+            .flat_map(|(_, actions)| actions.iter().map(|(keys, _)| keys.width()))
+            .max()
+            .unwrap_or(0);
+        let mut help_text = Vec::new();
+        for (context, actions) in &sections {
+            if !help_text.is_empty() {
+                help_text.push(Line::from(""));
+            }
+            help_text.push(Line::from(Span::styled(
+                format!(" {}", context.title()),
+                self.theme.header(),
+            )));
+            for (keys, description) in actions {
+                let padding = " ".repeat(key_width - keys.width());
+                help_text.push(Line::from(vec![
+                    Span::styled(format!(" {keys}{padding}  "), self.theme.accent()),
+                    Span::raw(description.clone()),
+                ]));
+            }
         }
-        let help_text = vec![
-            Line::from(vec![
-                Span::styled(" Key ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw("    Action"),
-            ]),
-            Line::from(""),
-            Line::from(" q, Esc   Quit"),
-            Line::from(" Space    Pause / resume list updates"),
-            Line::from(" Enter    Show process info"),
-            Line::from(" ↑ / ↓    Move selection"),
-            Line::from(" PgUp/Dn  Scroll by page"),
-            Line::from(" i        Toggle IPv4 / IPv6 filter"),
-            Line::from(" p        Toggle TCP / UDP filter"),
-            Line::from(" d        Toggle DNS resolution"),
-            Line::from(" h        This help"),
-            Line::from(" 1-9      Sort by column"),
-            Line::from(""),
-            Line::from(vec![Span::styled(
-                " Press q or Esc to close ",
-                Style::default().fg(Color::DarkGray),
-            )]),
-        ];
-        let paragraph = Paragraph::new(help_text)
+        let close_keys = self
+            .key_bindings
+            .actions(KeyContext::Global)
+            .into_iter()
+            .find(|(action, _)| *action == Action::Quit)
+            .map(|(_, chords)| {
+                let keys: Vec<_> = chords.iter().take(2).map(ToString::to_string).collect();
+                keys.join(" or ")
+            })
+            .unwrap_or_default();
+        let hint = format!(" Press {close_keys} to close, ↑↓ scrolls ");
+        let width = help_text
+            .iter()
+            .map(Line::width)
+            .chain([hint.width()])
+            .max()
+            .unwrap_or(0) as u16
+            + 3;
+        let height = help_text.len() as u16 + 2;
+        let help_area = overlay_area(
+            area,
+            width.min(area.width),
+            height.min(area.height),
+            overlay_style,
+            buf,
+        );
+        let max_scroll = help_text
+            .len()
+            .saturating_sub(help_area.height.saturating_sub(2) as usize);
+        let scroll = self.scroll_help.get().min(max_scroll);
+        self.scroll_help.set(scroll);
+        Paragraph::new(help_text)
             .block(
                 Block::default()
                     .title(" Help ")
+                    .title_bottom(Line::styled(hint, self.theme.hint()))
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
                     .style(overlay_style),
             )
             .style(overlay_style)
-            .wrap(Wrap { trim: true });
-        paragraph.render(help_area, buf);
+            .scroll((scroll as u16, 0))
+            .render(help_area, buf);
     }
 
     fn render_process_info(&self, area: Rect, buf: &mut Buffer) {
-        let table_height = area.height as usize;
-        self.visible_table_height
-            .set(table_height.saturating_sub(1));
-        let visible_table_width = area.width.saturating_sub(2);
+        let block = Block::default()
+            .title("Process Info")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(inner);
+
+        let selected_tab = ProcessTab::ALL
+            .iter()
+            .position(|tab| *tab == self.process_tab)
+            .unwrap_or(0);
+        Tabs::new(ProcessTab::ALL.iter().map(ProcessTab::title))
+            .select(selected_tab)
+            .highlight_style(self.theme.selected())
+            .block(Block::default().borders(Borders::BOTTOM))
+            .render(layout[0], buf);
+
+        self.visible_table_height.set(layout[1].height as usize);
+        match self.process_tab {
+            ProcessTab::Info => self.render_process_properties(layout[1], buf),
+            ProcessTab::Tree => self.render_process_tree(layout[1], buf),
+            ProcessTab::Sockets => self.render_process_sockets(layout[1], buf),
+            ProcessTab::Files => self.render_process_files(layout[1], buf),
+            ProcessTab::Environment => self.render_process_environment(layout[1], buf),
+        }
+    }
+
+    fn render_process_properties(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+        self.render_process_trends(layout[0], buf);
+        let area = layout[1];
+        self.visible_table_height.set(area.height as usize);
+        let visible_table_width = area.width;
 
-        if let Some(selection) = &self.selected {
-            let pid = selection.pid;
+        if let Some(pid) = self.process_info_pid {
             let column_width_property = 15;
             let column_width_value = visible_table_width.saturating_sub(column_width_property + 1);
 
@@ -242,17 +463,36 @@ impl App {
             let rows = if cache_hit {
                 self.process_info_cache.borrow().as_ref().unwrap().2.clone()
             } else {
-                let new_rows =
-                    process_info_to_rows(Pid::from_u32(pid), column_width_value as usize);
+                let socket_owners = self
+                    .selected
+                    .as_ref()
+                    .filter(|selected| selected.pid == pid)
+                    .map_or(&[][..], |selected| &selected.pids);
+                let new_rows = match &self.source {
+                    Source::Replay(replay) => recorded_process_rows(
+                        replay,
+                        pid,
+                        socket_owners,
+                        column_width_value as usize,
+                    ),
+                    Source::Live(_) => process_info_to_rows(
+                        &self.process_system,
+                        &self.users,
+                        Pid::from_u32(pid),
+                        socket_owners,
+                        column_width_value as usize,
+                    ),
+                };
                 *self.process_info_cache.borrow_mut() =
                     Some((pid, column_width_value as usize, new_rows.clone()));
                 new_rows
             };
             self.process_info_list_length.set(rows.len());
 
-            let title = "Process Info";
-
-            let scroll_position = self.scroll_process_info.get().min(rows.len() - 1);
+            let scroll_position = self
+                .scroll_process_info
+                .get()
+                .min(rows.len().saturating_sub(1));
 
             let rows_to_show = &rows[scroll_position
                 ..(scroll_position + self.visible_table_height.get()).min(rows.len())];
@@ -263,78 +503,979 @@ impl App {
                     Constraint::Length(column_width_property), // Process property
                     Constraint::Length(column_width_value),    // Value
                 ],
-            )
-            .block(
-                Block::default()
-                    .title(title)
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Double),
             );
 
             table.render(area, buf);
         }
     }
-}
 
-fn render_connections_header(sort_col: SortColumn, sort_order: SortOrder) -> Row<'static> {
-    use SortColumn::*;
+    /// Renders trend charts of the CPU and memory usage of the shown process.
+    fn render_process_trends(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let (cpu, memory) = self.process_history.back().copied().unwrap_or_default();
+        // Sparklines show integers, so record CPU usage in tenths of a percent
+        let cpu_history = self
+            .process_history
+            .iter()
+            .map(|(cpu, _)| (cpu * 10.0) as u64)
+            .collect::<Vec<_>>();
+        let memory_history = self
+            .process_history
+            .iter()
+            .map(|(_, memory)| *memory)
+            .collect::<Vec<_>>();
+        for (area, title, data, max, style) in [
+            (
+                layout[0],
+                format!(" CPU {cpu:.1} % "),
+                cpu_history,
+                1000,
+                self.theme.chart(),
+            ),
+            (
+                layout[1],
+                format!(" Memory {} ", bytesize::ByteSize::b(memory)),
+                memory_history,
+                0,
+                self.theme.accent(),
+            ),
+        ] {
+            // Show the newest samples if the chart is narrower than the history
+            let skip = data
+                .len()
+                .saturating_sub(area.width.saturating_sub(2) as usize);
+            let max = data.iter().copied().max().unwrap_or(0).max(max).max(1);
+            Sparkline::default()
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .data(&data[skip..])
+                .max(max)
+                .style(style)
+                .render(area, buf);
+        }
+    }
+
+    fn render_process_tree(&self, area: Rect, buf: &mut Buffer) {
+        let height = area.height as usize;
+        let first = (self.process_tree_cursor + 1).saturating_sub(height);
+        let lines = self
+            .process_tree
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(height)
+            .map(|(index, node)| {
+                let mut style = Style::default();
+                if Some(node.pid) == self.process_info_pid {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                if index == self.process_tree_cursor {
+                    style = style.patch(self.theme.selected());
+                }
+                let sockets = match node.socket_count {
+                    0 => String::new(),
+                    1 => "  (1 socket)".to_string(),
+                    count => format!("  ({count} sockets)"),
+                };
+                Line::from(Span::styled(
+                    format!(
+                        "{}{} {}{sockets}",
+                        "  ".repeat(node.depth),
+                        node.pid,
+                        node.name
+                    ),
+                    style,
+                ))
+            })
+            .collect::<Vec<_>>();
+        Paragraph::new(lines).render(area, buf);
+    }
 
-    let arrow = match sort_order {
-        SortOrder::Asc => " ↑",
-        SortOrder::Desc => " ↓",
-    };
+    fn render_process_sockets(&self, area: Rect, buf: &mut Buffer) {
+        const COLUMNS: [SortColumn; 7] = [
+            SortColumn::Proto,
+            SortColumn::LocalIP,
+            SortColumn::LocalPort,
+            SortColumn::RemoteIP,
+            SortColumn::RemotePort,
+            SortColumn::State,
+            SortColumn::DataRate,
+        ];
+        // The tab lists the rows of the connection table, so say what the filters hide
+        let sockets = self.process_sockets();
+        let hidden = self
+            .process_info_pid
+            .and_then(|pid| self.pid_socket_counts.get(&pid))
+            .map_or(0, |count| count.saturating_sub(sockets.len()));
+        let area = if hidden > 0 {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            let sockets_are = if hidden == 1 {
+                "socket is"
+            } else {
+                "sockets are"
+            };
+            Paragraph::new(format!(
+                "{hidden} more {sockets_are} hidden by the filters of the connection table"
+            ))
+            .style(self.theme.hint())
+            .render(layout[1], buf);
+            layout[0]
+        } else {
+            area
+        };
+        if sockets.is_empty() {
+            Paragraph::new("No connections owned by this process").render(area, buf);
+            return;
+        }
+
+        let height = (area.height as usize).saturating_sub(1);
+        let first = (self.process_socket_cursor + 1).saturating_sub(height);
+        let rows = sockets
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(height)
+            .map(|(index, entry)| {
+                let style = if index == self.process_socket_cursor {
+                    self.theme.selected()
+                } else {
+                    Style::default()
+                };
+                Row::new(COLUMNS.iter().map(|column| entry.column_text(*column))).style(style)
+            })
+            .collect::<Vec<_>>();
+        let widths = COLUMNS
+            .iter()
+            .map(|column| Constraint::Length(column.width()));
+        Table::new(rows, widths)
+            .header(render_connections_header(
+                &COLUMNS
+                    .iter()
+                    .map(|column| (TableColumn::single(*column), column.width()))
+                    .collect::<Vec<_>>(),
+                &[],
+                self.theme.header(),
+            ))
+            .render(area, buf);
+    }
+
+    fn render_process_files(&self, area: Rect, buf: &mut Buffer) {
+        if self.process_files.is_empty() {
+            let text = if cfg!(target_os = "linux") {
+                "No open file descriptors (or no permission to read them)"
+            } else {
+                "Open file descriptors are only available on Linux"
+            };
+            Paragraph::new(text).render(area, buf);
+            return;
+        }
+
+        let height = (area.height as usize).saturating_sub(1);
+        let first = (self.process_file_cursor + 1).saturating_sub(height);
+        let rows = self
+            .process_files
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(height)
+            .map(|(index, file)| {
+                let style = if index == self.process_file_cursor {
+                    self.theme.selected()
+                } else {
+                    Style::default()
+                };
+                let connection = self
+                    .socket_connection(file)
+                    .map(|entry| {
+                        let remote = if entry.remote_ip.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" -> {}:{}", entry.remote_ip, entry.remote_port)
+                        };
+                        format!(
+                            "{} {}:{}{remote} {}",
+                            entry.proto, entry.local_ip, entry.local_port, entry.state
+                        )
+                    })
+                    .unwrap_or_default();
+                Row::new(vec![
+                    file.fd.to_string(),
+                    file.kind.to_string(),
+                    file.target.clone(),
+                    connection,
+                ])
+                .style(style)
+            })
+            .collect::<Vec<_>>();
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+        ];
+        Table::new(rows, widths)
+            .header(Row::new(vec!["FD", "Type", "Target", "Connection"]).style(self.theme.header()))
+            .render(area, buf);
+    }
+
+    fn render_process_environment(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        let search = if self.environment_search_active {
+            format!("/{}_", self.environment_search)
+        } else if self.environment_search.is_empty() {
+            "/ search".to_string()
+        } else {
+            format!("/{}", self.environment_search)
+        };
+        let mask = if self.mask_secrets {
+            "m show secrets"
+        } else {
+            "m mask secrets"
+        };
+        Paragraph::new(Line::from(vec![
+            Span::styled(search, self.theme.header()),
+            Span::styled(
+                format!(
+                    "   s sort by {}   {mask}",
+                    match self.environment_sort {
+                        EnvironmentSort::Name => EnvironmentSort::Value.label(),
+                        EnvironmentSort::Value => EnvironmentSort::Name.label(),
+                    }
+                ),
+                self.theme.hint(),
+            ),
+        ]))
+        .render(layout[0], buf);
+
+        let area = layout[1];
+        self.visible_table_height.set(area.height as usize);
+        let variables = self.environment_entries();
+        let name_width = variables
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, 30) as u16;
+        let value_width = area.width.saturating_sub(name_width + 1);
+
+        let mut rows = Vec::new();
+        for (name, values) in variables {
+            let lines = values
+                .iter()
+                .flat_map(|value| wrap_text(value, value_width as usize))
+                .collect::<Vec<_>>();
+            for (index, line) in lines.into_iter().enumerate() {
+                let name = if index == 0 { name } else { "" };
+                rows.push(Row::new(vec![
+                    Cell::from(name.to_string()).style(self.theme.accent()),
+                    Cell::from(line),
+                ]));
+            }
+        }
+        self.process_info_list_length.set(rows.len());
+        let scroll_position = self
+            .scroll_process_info
+            .get()
+            .min(rows.len().saturating_sub(1));
+        let rows_to_show = rows
+            .into_iter()
+            .skip(scroll_position)
+            .take(area.height as usize);
+        Table::new(
+            rows_to_show,
+            [
+                Constraint::Length(name_width),
+                Constraint::Length(value_width),
+            ],
+        )
+        .render(area, buf);
+    }
+
+    fn render_protocol_filter(&self, area: Rect, buf: &mut Buffer) {
+        let items = Protocol::ALL
+            .iter()
+            .map(|protocol| {
+                let check = if self.protocol_filter.is_shown(*protocol) {
+                    "x"
+                } else {
+                    " "
+                };
+                format!("[{check}] {}", protocol.label())
+            })
+            .collect();
+        self.render_selection_overlay(
+            area,
+            buf,
+            " Protocols ",
+            items,
+            " Space toggles, Esc closes ",
+        );
+    }
+
+    fn render_user_filter(&self, area: Rect, buf: &mut Buffer) {
+        let items = self
+            .user_filter_options()
+            .into_iter()
+            .map(|option| {
+                let check = if option == self.user_filter { "*" } else { " " };
+                let label = match option {
+                    UserFilter::AllUsers => "All users".to_string(),
+                    UserFilter::CurrentUser => format!("My sockets ({})", self.current_user),
+                    UserFilter::User(name) => name,
+                };
+                format!("({check}) {label}")
+            })
+            .collect();
+        self.render_selection_overlay(area, buf, " Users ", items, " Enter selects, Esc closes ");
+    }
+
+    fn render_namespace_filter(&self, area: Rect, buf: &mut Buffer) {
+        let items = self
+            .namespace_filter_options()
+            .into_iter()
+            .map(|option| {
+                let check = if option == self.namespace_filter {
+                    "*"
+                } else {
+                    " "
+                };
+                format!(
+                    "({check}) {}",
+                    option.as_deref().unwrap_or("All namespaces")
+                )
+            })
+            .collect();
+        self.render_selection_overlay(
+            area,
+            buf,
+            " Namespaces ",
+            items,
+            " Enter selects, Esc closes ",
+        );
+    }
+
+    fn render_cgroup_filter(&self, area: Rect, buf: &mut Buffer) {
+        let items = self
+            .cgroup_filter_options()
+            .into_iter()
+            .map(|option| {
+                let check = if option == self.cgroup_filter {
+                    "*"
+                } else {
+                    " "
+                };
+                format!("({check}) {}", option.as_deref().unwrap_or("All units"))
+            })
+            .collect();
+        self.render_selection_overlay(
+            area,
+            buf,
+            " Units / Containers ",
+            items,
+            " Enter selects, Esc closes ",
+        );
+    }
+
+    fn render_bulk_actions(&self, area: Rect, buf: &mut Buffer) {
+        let marked = self.marked_entries().len();
+        let title = if marked > 0 {
+            format!(" {marked} marked rows ")
+        } else {
+            " Selected row ".to_string()
+        };
+        let items = BulkAction::ALL
+            .iter()
+            .map(|action| action.label())
+            .collect();
+        self.render_selection_overlay(area, buf, &title, items, " Enter runs, Esc closes ");
+    }
+
+    fn render_signal_confirmation(&self, area: Rect, buf: &mut Buffer) {
+        let Some(pending) = &self.pending_signal else {
+            return;
+        };
+        let mut items: Vec<String> = pending
+            .processes
+            .iter()
+            .map(|(pid, name, _)| format!("{pid:>8}  {name}"))
+            .collect();
+        if !pending.skipped.is_empty() {
+            let skipped = pending
+                .skipped
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            items.push(format!("Skipped PID {skipped}: init and netview"));
+        }
+        self.render_selection_overlay(
+            area,
+            buf,
+            &format!(
+                " Send {} to {} processes? ",
+                pending.name,
+                pending.processes.len()
+            ),
+            items,
+            " y sends, n or Esc cancels ",
+        );
+    }
+
+    fn render_copy_menu(&self, area: Rect, buf: &mut Buffer) {
+        let items = CopyField::ALL
+            .iter()
+            .map(|field| match field.template() {
+                Some(template) => format!("{:<26}{template}", field.label()),
+                None => format!("{:<26}{}", field.label(), self.config.copy_template()),
+            })
+            .collect();
+        self.render_selection_overlay(area, buf, " Copy ", items, " Enter copies, Esc closes ");
+    }
+
+    fn render_export_menu(&self, area: Rect, buf: &mut Buffer) {
+        let items = ExportFormat::ALL
+            .iter()
+            .map(|format| format!("{:<16}.{}", format.label(), format.extension()))
+            .collect();
+        self.render_selection_overlay(
+            area,
+            buf,
+            &format!(" Export {} connections ", self.entries.len()),
+            items,
+            " Enter writes the file, Esc closes ",
+        );
+    }
+
+    fn render_column_chooser(&self, area: Rect, buf: &mut Buffer) {
+        let items = self
+            .available_columns()
+            .iter()
+            .map(|layout| {
+                let check = if layout.visible { "x" } else { " " };
+                let width = layout
+                    .width
+                    .map_or("auto".to_string(), |width| width.to_string());
+                format!("[{check}] {:<16}{width:>5}", layout.column.label())
+            })
+            .collect();
+        self.render_selection_overlay(
+            area,
+            buf,
+            " Columns ",
+            items,
+            " Space shows/hides, Shift+↑↓ moves, ←→ resizes, a fits ",
+        );
+    }
 
-    let header_cells = vec![
-        ("Prot", Proto),
-        ("Local IP", LocalIP),
-        ("LPort", LocalPort),
-        ("Remote IP", RemoteIP),
-        ("RPort", RemotePort),
-        ("State", State),
-        ("PID", PID),
-        ("Process", Process),
-        ("Rate", DataRate),
-    ]
-    .into_iter()
-    .map(|(label, col)| {
-        let text = if col == sort_col {
-            format!("{label}{arrow}")
+    /// Columns of the connection table with their widths, fitted into `available` terminal
+    /// columns. If the content does not fit, addresses and ports are collapsed into `ip:port`
+    /// cells, then low priority columns are dropped and finally content based widths shrink.
+    pub(crate) fn table_layout(&self, available: u16) -> Vec<(TableColumn, u16)> {
+        let visible = self.visible_columns();
+        let mut columns = if self.config.compact_rows {
+            TableColumn::compact(&visible)
         } else {
-            label.to_string()
+            visible.iter().copied().map(TableColumn::single).collect()
         };
-        Cell::from(text).style(Style::default().add_modifier(Modifier::BOLD))
-    })
-    .collect::<Vec<_>>();
+        // Table columns are separated by one space
+        let total = |widths: &mut dyn Iterator<Item = u16>, count: usize| {
+            widths.sum::<u16>() + count.saturating_sub(1) as u16
+        };
+
+        let mut preferred = columns
+            .iter()
+            .map(|column| self.preferred_width(column))
+            .collect::<Vec<_>>();
+        if !self.config.compact_rows
+            && total(&mut preferred.iter().copied(), columns.len()) > available
+        {
+            columns = TableColumn::collapse_endpoints(columns);
+        }
+        while columns.len() > 1
+            && total(
+                &mut columns.iter().map(|c| self.minimum_width(c)),
+                columns.len(),
+            ) > available
+        {
+            let lowest = columns
+                .iter()
+                .enumerate()
+                .min_by_key(|(index, column)| (column.priority(), usize::MAX - index))
+                .map(|(index, _)| index)
+                .unwrap_or(0);
+            columns.remove(lowest);
+        }
+
+        preferred = columns
+            .iter()
+            .map(|column| self.preferred_width(column))
+            .collect();
+        while total(&mut preferred.iter().copied(), columns.len()) > available {
+            let shrinkable = columns
+                .iter()
+                .zip(&preferred)
+                .enumerate()
+                .filter(|(_, (column, width))| {
+                    self.configured_width(column).is_none() && **width > self.minimum_width(column)
+                })
+                .max_by_key(|(_, (_, width))| **width)
+                .map(|(index, _)| index);
+            let Some(index) = shrinkable else {
+                break;
+            };
+            preferred[index] -= 1;
+        }
+        columns.into_iter().zip(preferred).collect()
+    }
+
+    /// Width set in the column chooser for a column showing a single field
+    fn configured_width(&self, column: &TableColumn) -> Option<u16> {
+        let field = column.single_field()?;
+        self.config
+            .columns
+            .iter()
+            .find(|layout| layout.column == field)
+            .and_then(|layout| layout.width)
+    }
+
+    /// Configured width, or the width fitting header and content
+    fn preferred_width(&self, column: &TableColumn) -> u16 {
+        if let Some(width) = self.configured_width(column) {
+            return width;
+        }
+        if let Some(field) = column.single_field() {
+            return self.content_width(field);
+        }
+        let maximum = column
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|field| field.width() + 1).sum::<u16>())
+            .max()
+            .unwrap_or(0);
+        self.entries
+            .iter()
+            .flat_map(|entry| column.lines(entry))
+            .map(|line| line.width() as u16)
+            .max()
+            .unwrap_or(0)
+            .min(maximum)
+            .max(column.header_width())
+    }
 
-    Row::new(header_cells)
+    /// Width a column needs at least before it is dropped from a narrow table
+    fn minimum_width(&self, column: &TableColumn) -> u16 {
+        self.configured_width(column).unwrap_or_else(|| {
+            self.preferred_width(column)
+                .min(column.readable_width())
+                .max(column.header_width())
+        })
+    }
+
+    /// Renders a list of choices with the line at `overlay_cursor` highlighted above the table.
+    fn render_selection_overlay(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        title: &str,
+        items: Vec<String>,
+        hint: &str,
+    ) {
+        self.render_connection_table(area, buf);
+        let max_items = area.height.saturating_sub(6) as usize;
+        let width = items
+            .iter()
+            .map(|item| item.chars().count())
+            .chain([hint.chars().count(), title.chars().count()])
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let height = items.len().min(max_items) as u16 + 4;
+        let overlay = overlay_area(area, width, height, self.theme.normal(), buf);
+        let first = (self.overlay_cursor + 1).saturating_sub(max_items);
+        let mut lines = items
+            .into_iter()
+            .enumerate()
+            .skip(first)
+            .take(max_items)
+            .map(|(index, item)| {
+                let style = if index == self.overlay_cursor {
+                    self.theme.selected()
+                } else {
+                    Style::default()
+                };
+                Line::from(Span::styled(format!(" {item}"), style))
+            })
+            .collect::<Vec<_>>();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            hint.to_string(),
+            self.theme.hint(),
+        )]));
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title.to_string())
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .render(overlay, buf);
+    }
 }
 
-fn wrap_text(text: &str, max_length: usize) -> Vec<String> {
-    if max_length == 0 {
+/// Clears and returns a centered area of the given size for an overlay.
+fn overlay_area(area: Rect, width: u16, height: u16, style: Style, buf: &mut Buffer) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(width),
+            Constraint::Min(0),
+        ])
+        .split(vertical[1]);
+    let overlay = horizontal[1];
+    Clear.render(overlay, buf);
+    for y in overlay.top()..overlay.bottom() {
+        buf.set_string(overlay.x, y, " ".repeat(overlay.width as usize), style);
+    }
+    overlay
+}
+
+/// Header of the connection table. Sort columns get an arrow, and a priority number if sorted by
+/// more than one column.
+fn render_connections_header(
+    columns: &[(TableColumn, u16)],
+    sort_keys: &[(SortColumn, SortOrder)],
+    style: Style,
+) -> Row<'static> {
+    let header_cells = columns
+        .iter()
+        .map(|(column, _)| {
+            let text = column
+                .lines
+                .iter()
+                .map(|line| {
+                    let label = TableColumn::label(line);
+                    let Some(priority) = sort_keys
+                        .iter()
+                        .position(|(column, _)| line.contains(column))
+                    else {
+                        return label.to_string();
+                    };
+                    let arrow = match sort_keys[priority].1 {
+                        SortOrder::Asc => "↑",
+                        SortOrder::Desc => "↓",
+                    };
+                    if sort_keys.len() > 1 {
+                        format!("{label} {arrow}{}", priority + 1)
+                    } else {
+                        format!("{label} {arrow}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Cell::from(text).style(style)
+        })
+        .collect::<Vec<_>>();
+    let height = columns
+        .iter()
+        .map(|(column, _)| column.lines.len())
+        .max()
+        .unwrap_or(1) as u16;
+
+    Row::new(header_cells).height(height)
+}
+
+/// A column of the connection table. Each line of its cells shows one field of an entry, or an
+/// address and port collapsed into an `ip:port` endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TableColumn {
+    pub lines: Vec<Vec<SortColumn>>,
+}
+
+impl TableColumn {
+    fn single(column: SortColumn) -> Self {
+        Self {
+            lines: vec![vec![column]],
+        }
+    }
+
+    /// Two lines per row: related fields are stacked in one column
+    fn compact(visible: &[SortColumn]) -> Vec<Self> {
+        use SortColumn::*;
+        let shown = |fields: &[SortColumn]| {
+            fields
+                .iter()
+                .copied()
+                .filter(|field| visible.contains(field))
+                .collect::<Vec<_>>()
+        };
+        [
+            [&[Proto][..], &[State]],
+            [&[LocalIP, LocalPort], &[RemoteIP, RemotePort]],
+            [&[PID], &[Process]],
+            [&[DataRate], &[User]],
+            [&[Namespace], &[Cgroup]],
+        ]
+        .iter()
+        .map(|lines| Self {
+            lines: lines
+                .iter()
+                .map(|fields| shown(fields))
+                .filter(|fields| !fields.is_empty())
+                .collect(),
+        })
+        .filter(|column| !column.lines.is_empty())
+        .collect()
+    }
+
+    /// Replaces separate address and port columns by `ip:port` columns.
+    fn collapse_endpoints(columns: Vec<Self>) -> Vec<Self> {
+        use SortColumn::*;
+        let has = |field| columns.iter().any(|c| c.single_field() == Some(field));
+        let mut collapsed = Vec::new();
+        for column in columns.iter() {
+            match column.single_field() {
+                Some(LocalIP) if has(LocalPort) => collapsed.push(Self {
+                    lines: vec![vec![LocalIP, LocalPort]],
+                }),
+                Some(RemoteIP) if has(RemotePort) => collapsed.push(Self {
+                    lines: vec![vec![RemoteIP, RemotePort]],
+                }),
+                Some(LocalPort) if has(LocalIP) => {}
+                Some(RemotePort) if has(RemoteIP) => {}
+                _ => collapsed.push(column.clone()),
+            }
+        }
+        collapsed
+    }
+
+    fn single_field(&self) -> Option<SortColumn> {
+        match self.lines.as_slice() {
+            [line] if line.len() == 1 => Some(line[0]),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, field: SortColumn) -> bool {
+        self.lines.iter().any(|line| line.contains(&field))
+    }
+
+    /// Columns with the lowest priority are dropped first when the terminal is too narrow.
+    fn priority(&self) -> u8 {
+        self.lines
+            .iter()
+            .flatten()
+            .map(SortColumn::priority)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn label(line: &[SortColumn]) -> &'static str {
+        match line {
+            [SortColumn::LocalIP, SortColumn::LocalPort] => "Local",
+            [SortColumn::RemoteIP, SortColumn::RemotePort] => "Remote",
+            [field, ..] => field.label(),
+            [] => "",
+        }
+    }
+
+    /// Width below which values get hard to read, e.g. an IPv4 address and port
+    fn readable_width(&self) -> u16 {
+        self.lines
+            .iter()
+            .map(|line| match line.as_slice() {
+                [SortColumn::LocalIP | SortColumn::RemoteIP, _] => 21,
+                [SortColumn::LocalIP | SortColumn::RemoteIP] => 15,
+                [SortColumn::State] => 11,
+                [SortColumn::Process] => 12,
+                [SortColumn::DataRate] => 9,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Width of the widest header line including the sort arrow
+    fn header_width(&self) -> u16 {
+        self.lines
+            .iter()
+            // Room for the sort arrow and priority
+            .map(|line| Self::label(line).width() as u16 + 3)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// True if the table search looks at a field of this column
+    fn is_searched(&self) -> bool {
+        self.lines.iter().flatten().any(|field| {
+            matches!(
+                field,
+                SortColumn::LocalIP
+                    | SortColumn::LocalPort
+                    | SortColumn::RemoteIP
+                    | SortColumn::RemotePort
+                    | SortColumn::Process
+            )
+        })
+    }
+
+    /// Text of each line of the cell of `entry`
+    fn lines(&self, entry: &ConnectionEntry) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| match line.as_slice() {
+                [ip @ (SortColumn::LocalIP | SortColumn::RemoteIP), port] => {
+                    entry.endpoint(*ip, *port)
+                }
+                fields => fields
+                    .iter()
+                    .map(|field| entry.column_text(*field))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect()
+    }
+
+    /// Cell text of `entry`; addresses wider than the column are shortened in the middle.
+    fn text(&self, entry: &ConnectionEntry, width: u16) -> String {
+        self.lines(entry)
+            .into_iter()
+            .zip(&self.lines)
+            .map(|(text, line)| {
+                let is_address = line
+                    .iter()
+                    .any(|field| matches!(field, SortColumn::LocalIP | SortColumn::RemoteIP));
+                if is_address && text.width() > width as usize {
+                    ellipsize_middle(&text, width as usize)
+                } else {
+                    text
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Lines of `text` with occurrences of `search` (ignoring ASCII case) in `style`
+fn highlight_matches(text: &str, search: &str, style: Style) -> Text<'static> {
+    if search.is_empty() {
+        return Text::from(text.to_string());
+    }
+    let search = search.to_ascii_lowercase();
+    let lines = text.split('\n').map(|line| {
+        // ASCII lower case keeps byte offsets, so they index into `line`
+        let lower = line.to_ascii_lowercase();
+        let mut spans = Vec::new();
+        let mut end = 0;
+        for (start, found) in lower.match_indices(&search) {
+            spans.push(Span::raw(line[end..start].to_string()));
+            spans.push(Span::styled(
+                line[start..start + found.len()].to_string(),
+                style,
+            ));
+            end = start + found.len();
+        }
+        spans.push(Span::raw(line[end..].to_string()));
+        Line::from(spans)
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+/// Shortens `text` to `width` terminal columns by replacing its middle with `…`.
+fn ellipsize_middle(text: &str, width: usize) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+    let tail_width = (width - 1) / 2;
+    let head_width = width - 1 - tail_width;
+    let mut head = String::new();
+    let mut used = 0;
+    for grapheme in &graphemes {
+        used += grapheme.width();
+        if used > head_width {
+            break;
+        }
+        head.push_str(grapheme);
+    }
+    let mut tail = Vec::new();
+    used = 0;
+    for grapheme in graphemes.iter().rev() {
+        used += grapheme.width();
+        if used > tail_width {
+            break;
+        }
+        tail.push(*grapheme);
+    }
+    tail.reverse();
+    format!("{head}…{}", tail.concat())
+}
+
+/// Splits `text` into lines of at most `max_width` terminal columns. Lines preferably end after
+/// a space, `/` or `=`; words without such a break are split between grapheme clusters.
+fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    if max_width == 0 {
         return vec![text.to_string()];
     }
 
+    let is_break = |grapheme: &str| matches!(grapheme, " " | "/" | "=");
     let mut lines = Vec::new();
-    let mut remaining = text;
+    // Graphemes of the current line with their display widths
+    let mut line: Vec<(&str, usize)> = Vec::new();
+    let mut width = 0;
+    // Number of graphemes up to and including the last break character of the line
+    let mut break_after: Option<usize> = None;
 
-    while !remaining.is_empty() {
-        if remaining.len() <= max_length {
-            lines.push(remaining.to_string());
-            break;
-        } else {
-            let (line, rest) = remaining.split_at(max_length);
-            lines.push(line.to_string());
-            remaining = rest;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        while width + grapheme_width > max_width && !line.is_empty() {
+            let rest = line.split_off(break_after.unwrap_or(line.len()));
+            let finished = line.iter().map(|(g, _)| *g).collect::<String>();
+            lines.push(finished.trim_end_matches(' ').to_string());
+            line = rest;
+            width = line.iter().map(|(_, w)| w).sum();
+            break_after = line
+                .iter()
+                .rposition(|(g, _)| is_break(g))
+                .map(|index| index + 1);
         }
+        line.push((grapheme, grapheme_width));
+        width += grapheme_width;
+        if is_break(grapheme) {
+            break_after = Some(line.len());
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line.iter().map(|(g, _)| *g).collect());
     }
 
     lines
 }
 
-fn process_info_to_rows(pid: Pid, max_value_width: usize) -> Vec<Row<'static>> {
-    let system = sysinfo::System::new_all();
-    let users = sysinfo::Users::new_with_refreshed_list();
-
+/// Rows describing process `pid`. `socket_owners` are all processes sharing the selected socket.
+fn process_info_to_rows(
+    system: &System,
+    users: &Users,
+    pid: Pid,
+    socket_owners: &[u32],
+    max_value_width: usize,
+) -> Vec<Row<'static>> {
     let normal = Style::default();
     if let Some(process) = system.process(pid) {
         let process_name = process.name().to_string_lossy().to_string();
@@ -380,10 +1521,48 @@ fn process_info_to_rows(pid: Pid, max_value_width: usize) -> Vec<Row<'static>> {
             .to_string();
         let run_time = format_duration(Duration::from_secs(process.run_time())).to_string();
         let status = process.status().to_string();
+        let parent = process
+            .parent()
+            .map(|parent| {
+                let name = system
+                    .process(parent)
+                    .map(|p| p.name().to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{parent} {name}")
+            })
+            .unwrap_or("none".to_string());
+        let cpu = format!("{:.1} %", process.cpu_usage());
+        let disk_usage = process.disk_usage();
+        let disk_read = format!(
+            "{} (+{})",
+            bytesize::ByteSize::b(disk_usage.total_read_bytes),
+            bytesize::ByteSize::b(disk_usage.read_bytes)
+        );
+        let disk_written = format!(
+            "{} (+{})",
+            bytesize::ByteSize::b(disk_usage.total_written_bytes),
+            bytesize::ByteSize::b(disk_usage.written_bytes)
+        );
+        let threads = process
+            .tasks()
+            .map(|tasks| tasks.len().to_string())
+            .unwrap_or("not available".to_string());
+        let open_files = process
+            .open_files()
+            .map(|count| count.to_string())
+            .unwrap_or("not available".to_string());
+        #[cfg(target_os = "linux")]
+        let nice = crate::procfs::read_nice(pid_u32);
+        #[cfg(not(target_os = "linux"))]
+        let nice: Option<i32> = None;
+        let nice = nice
+            .map(|nice| nice.to_string())
+            .unwrap_or("not available".to_string());
 
         let mut process_info = vec![
             ("Name:", process_name),
             ("Pid:", pid_u32.to_string()),
+            ("Parent:", parent),
             ("User:", user),
             ("Effective user:", effective_user),
             ("Path:", path),
@@ -394,34 +1573,41 @@ fn process_info_to_rows(pid: Pid, max_value_width: usize) -> Vec<Row<'static>> {
             ("Started:", started),
             ("Run time:", run_time),
             ("Status:", status),
+            ("CPU usage:", cpu),
+            ("Threads:", threads),
+            ("Open files:", open_files),
+            ("Nice:", nice),
+            ("Disk read:", disk_read),
+            ("Disk written:", disk_written),
         ];
-        for env in process.environ() {
-            process_info.push(("env", env.to_string_lossy().to_string()));
+        if socket_owners.len() > 1 {
+            for (index, owner) in socket_owners.iter().enumerate() {
+                let owner_name = system
+                    .process(Pid::from_u32(*owner))
+                    .map(|p| p.name().to_string_lossy().to_string())
+                    .unwrap_or("not available".to_string());
+                let property = if index == 0 { "Socket owners:" } else { "" };
+                process_info.push((property, format!("{owner} {owner_name}")));
+            }
         }
-
-        let mut rows = Vec::new();
-        for (property, value) in process_info {
-            // Split long values into lines that fit within the available width
-            let wrapped_lines = wrap_text(&value, max_value_width);
-
-            // First line with property name
-            if let Some(first_line) = wrapped_lines.first() {
-                rows.push(Row::new(vec![
-                    Cell::from(property.to_string()).style(normal),
-                    Cell::from(first_line.clone()).style(normal),
-                ]));
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = crate::cgroup::read_cgroup(pid_u32) {
+            process_info.push(("Cgroup:", cgroup.path));
+            if let Some(unit) = cgroup.systemd_unit {
+                process_info.push(("Systemd unit:", unit));
             }
-
-            // Additional lines with empty property cell
-            for line in wrapped_lines.iter().skip(1) {
-                rows.push(Row::new(vec![
-                    Cell::from("").style(normal),
-                    Cell::from(line.clone()).style(normal),
-                ]));
+            if let Some(container) = cgroup.container {
+                process_info.push((
+                    "Container:",
+                    format!("{} {}", container.runtime, container.id),
+                ));
+            }
+            if let Some(pod_uid) = cgroup.pod_uid {
+                process_info.push(("Pod UID:", pod_uid));
             }
         }
 
-        rows
+        property_rows(process_info, max_value_width)
     } else {
         vec![Row::new(vec![
             Cell::from("Process".to_string()).style(normal),
@@ -429,3 +1615,125 @@ fn process_info_to_rows(pid: Pid, max_value_width: usize) -> Vec<Row<'static>> {
         ])]
     }
 }
+
+/// Rows of the info tab for process `pid` of a replay, from the metadata recorded up to the
+/// shown snapshot
+fn recorded_process_rows(
+    replay: &Replay,
+    pid: u32,
+    socket_owners: &[u32],
+    max_value_width: usize,
+) -> Vec<Row<'static>> {
+    let index = replay.position();
+    let name = |pid: u32| {
+        replay
+            .process(index, pid)
+            .map(|process| process.name.clone())
+            .unwrap_or("not recorded".to_string())
+    };
+    let Some(process) = replay.process(index, pid) else {
+        return property_rows(
+            vec![("Process", "not recorded".to_string())],
+            max_value_width,
+        );
+    };
+    let parent = process
+        .parent
+        .map(|parent| format!("{parent} {}", name(parent)))
+        .unwrap_or("none".to_string());
+    let mut process_info = vec![
+        ("Name:", process.name.clone()),
+        ("Pid:", pid.to_string()),
+        ("Parent:", parent),
+        ("User:", process.user.clone()),
+        ("Path:", process.exe.clone()),
+        ("Command line:", process.cmdline.clone()),
+        ("Unit/Container:", process.cgroup.clone()),
+        (
+            "Recorded:",
+            replay
+                .snapshot(index)
+                .time()
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ),
+    ];
+    if socket_owners.len() > 1 {
+        for (index, owner) in socket_owners.iter().enumerate() {
+            let property = if index == 0 { "Socket owners:" } else { "" };
+            process_info.push((property, format!("{owner} {}", name(*owner))));
+        }
+    }
+    property_rows(process_info, max_value_width)
+}
+
+/// Table rows of (property, value) pairs; long values are wrapped into further rows
+fn property_rows(properties: Vec<(&str, String)>, max_value_width: usize) -> Vec<Row<'static>> {
+    let normal = Style::default();
+    let mut rows = Vec::new();
+    for (property, value) in properties {
+        // Split long values into lines that fit within the available width
+        let wrapped_lines = wrap_text(&value, max_value_width);
+
+        // First line with property name
+        if let Some(first_line) = wrapped_lines.first() {
+            rows.push(Row::new(vec![
+                Cell::from(property.to_string()).style(normal),
+                Cell::from(first_line.clone()).style(normal),
+            ]));
+        }
+
+        // Additional lines with empty property cell
+        for line in wrapped_lines.iter().skip(1) {
+            rows.push(Row::new(vec![
+                Cell::from("").style(normal),
+                Cell::from(line.clone()).style(normal),
+            ]));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wrap_text;
+
+    #[test]
+    fn wrap_text_splits_multi_byte_text_between_characters() {
+        assert_eq!(wrap_text("grüße aus köln", 10), ["grüße aus", "köln"]);
+        assert_eq!(wrap_text("äöüäöü", 4), ["äöüä", "öü"]);
+    }
+
+    #[test]
+    fn wrap_text_keeps_wide_characters_within_the_width() {
+        assert_eq!(wrap_text("漢字かな", 4), ["漢字", "かな"]);
+        // A wide character never straddles the boundary
+        assert_eq!(wrap_text("漢字かな", 5), ["漢字", "かな"]);
+        assert_eq!(wrap_text("a漢字", 4), ["a漢", "字"]);
+    }
+
+    #[test]
+    fn wrap_text_keeps_grapheme_clusters_together() {
+        // e followed by a combining acute accent is one column
+        assert_eq!(wrap_text("cafe\u{301}s", 4), ["cafe\u{301}", "s"]);
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(wrap_text(&format!("ab{family}"), 3), ["ab", family]);
+    }
+
+    #[test]
+    fn wrap_text_breaks_after_space_slash_and_equals() {
+        assert_eq!(wrap_text("hello world", 8), ["hello", "world"]);
+        assert_eq!(wrap_text("/usr/local/bin", 8), ["/usr/", "local/", "bin"]);
+        assert_eq!(wrap_text("key=value", 6), ["key=", "value"]);
+    }
+
+    #[test]
+    fn wrap_text_puts_a_too_wide_character_on_its_own_line() {
+        assert_eq!(wrap_text("漢a", 1), ["漢", "a"]);
+    }
+
+    #[test]
+    fn wrap_text_of_an_empty_string_is_one_empty_line() {
+        assert_eq!(wrap_text("", 10), [""]);
+    }
+}